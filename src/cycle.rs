@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub tail: usize,
+    pub period: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CycleDetection<S> {
+    pub cycle: Cycle,
+    states: Vec<S>,
+}
+
+impl Cycle {
+    pub fn equivalent_step(&self, step: usize) -> usize {
+        if step < self.tail {
+            step
+        } else {
+            self.tail + (step - self.tail) % self.period
+        }
+    }
+
+    pub fn state_at<S: Clone, F: FnMut(&S) -> S>(&self, initial: &S, mut step: F, n: usize) -> S {
+        (0..self.equivalent_step(n)).fold(initial.clone(), |state, _| step(&state))
+    }
+}
+
+impl<S> CycleDetection<S> {
+    pub fn state_at(&self, step: usize) -> &S {
+        &self.states[self.cycle.equivalent_step(step)]
+    }
+
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+}
+
+pub fn detect_with_hashing<S, F>(initial: S, mut step: F) -> CycleDetection<S>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut first_seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+    loop {
+        if let Some(&tail) = first_seen.get(&state) {
+            return CycleDetection {
+                cycle: Cycle {
+                    tail,
+                    period: states.len() - tail,
+                },
+                states,
+            };
+        }
+        first_seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+pub fn state_after<S, F>(initial: S, mut step: F, n: usize) -> S
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut first_seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+    while states.len() < n {
+        if let Some(&tail) = first_seen.get(&state) {
+            let cycle = Cycle {
+                tail,
+                period: states.len() - tail,
+            };
+            return states.swap_remove(cycle.equivalent_step(n));
+        }
+        first_seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+    state
+}
+
+pub fn detect_with_brent<S, F>(initial: &S, mut step: F) -> Cycle
+where
+    S: PartialEq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+    let mut tortoise = initial.clone();
+    let mut hare = (0..period).fold(initial.clone(), |state, _| step(&state));
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+    Cycle { tail, period }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn squaring(modulus: u64) -> impl Fn(&u64) -> u64 {
+        move |x| (x * x + 1) % modulus
+    }
+
+    fn naive(initial: u64, step: impl Fn(&u64) -> u64, n: usize) -> u64 {
+        (0..n).fold(initial, |state, _| step(&state))
+    }
+
+    #[test]
+    fn brent_matches_hashing() {
+        for modulus in [1, 2, 10, 97, 255, 1000, 4097] {
+            for initial in [0, 3, modulus - 1] {
+                let hashing = detect_with_hashing(initial, squaring(modulus)).cycle;
+                let brent = detect_with_brent(&initial, squaring(modulus));
+                assert_eq!(
+                    brent, hashing,
+                    "x -> x * x + 1 mod {modulus} from {initial}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_point_has_period_one() {
+        let detection = detect_with_hashing(5u64, |x| *x);
+        assert_eq!(detection.cycle, Cycle { tail: 0, period: 1 });
+        assert_eq!(detection.states(), &[5]);
+        assert_eq!(detect_with_brent(&5u64, |x| *x), detection.cycle);
+    }
+
+    #[test]
+    fn states_match_a_naive_loop_around_the_tail() {
+        for modulus in [10, 97, 1000, 1024, 4097] {
+            let step = squaring(modulus);
+            let detection = detect_with_hashing(3, &step);
+            let Cycle { tail, period } = detection.cycle;
+            assert!(tail > 0, "mod {modulus} should have a tail");
+            for n in [0, tail - 1, tail, tail + 1, tail + period * 1000 + 3] {
+                let expected = naive(3, &step, n);
+                assert_eq!(*detection.state_at(n), expected, "mod {modulus}, n = {n}");
+                assert_eq!(detection.cycle.state_at(&3, &step, n), expected);
+                assert_eq!(state_after(3, &step, n), expected);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn state_after_matches_naive(
+            modulus in 1u64..500,
+            initial in 0u64..500,
+            n in 0usize..2000,
+        ) {
+            let initial = initial % modulus;
+            let step = squaring(modulus);
+            prop_assert_eq!(state_after(initial, &step, n), naive(initial, &step, n));
+            let cycle = detect_with_brent(&initial, &step);
+            prop_assert_eq!(cycle, detect_with_hashing(initial, &step).cycle);
+            prop_assert_eq!(cycle.state_at(&initial, &step, n), naive(initial, &step, n));
+        }
+    }
+}
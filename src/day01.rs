@@ -1,49 +1,132 @@
-fn solve_part_one() -> i32 {
-    return std::fs::read_to_string("input/day01")
-        .unwrap()
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let numbers = line
-                .chars()
-                .filter(|c| c.is_numeric())
-                .map(|c| c.to_string())
-                .collect::<Vec<String>>();
-            return format!("{}{}", numbers.first().unwrap(), numbers.last().unwrap())
-                .parse::<i32>()
-                .unwrap();
-        })
-        .sum();
-}
-
-fn solve_part_two() -> i32 {
-    let number_words = vec![
-        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-    ];
-    return std::fs::read_to_string("input/day01")
-        .unwrap()
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let mut numbers = Vec::new();
-            for (index, char) in line.chars().enumerate() {
-                if char.is_numeric() {
-                    numbers.push(char.to_string());
-                }
-                for (number_value, number_word) in number_words.iter().enumerate() {
-                    if index + number_word.len() > line.len() {
-                        continue;
-                    }
-                    if line[index..index + number_word.len()] == number_word.to_string() {
-                        numbers.push((number_value + 1).to_string());
-                    }
-                }
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+const NUMBER_WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitRules {
+    NumericOnly,
+    NumericAndWords,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineDiagnostic<'a> {
+    pub line_number: usize,
+    pub line: &'a str,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalibrationSum {
+    pub total: u64,
+    pub lines_read: usize,
+    pub lines_without_digit: usize,
+}
+
+#[derive(Debug)]
+pub enum CalibrationError {
+    Io(io::Error),
+    Overflow { line_number: usize },
+}
+
+impl From<io::Error> for CalibrationError {
+    fn from(error: io::Error) -> Self {
+        CalibrationError::Io(error)
+    }
+}
+
+impl std::fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalibrationError::Io(error) => {
+                write!(f, "failed to read calibration document: {}", error)
+            }
+            CalibrationError::Overflow { line_number } => {
+                write!(f, "calibration sum overflowed at line {}", line_number)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+fn digit_at(line: &[u8], index: usize, rules: DigitRules) -> Option<u64> {
+    if line[index].is_ascii_digit() {
+        return Some((line[index] - b'0') as u64);
+    }
+    if rules == DigitRules::NumericOnly {
+        return None;
+    }
+    NUMBER_WORDS
+        .iter()
+        .position(|number_word| line[index..].starts_with(number_word.as_bytes()))
+        .map(|number_value| number_value as u64 + 1)
+}
+
+pub fn calibration_value(line: &str, rules: DigitRules) -> Option<u64> {
+    let bytes = line.as_bytes();
+    let first = (0..bytes.len()).find_map(|index| digit_at(bytes, index, rules))?;
+    let last = (0..bytes.len())
+        .rev()
+        .find_map(|index| digit_at(bytes, index, rules))?;
+    Some(first * 10 + last)
+}
+
+pub fn sum_calibration_values<R: BufRead>(
+    mut reader: R,
+    rules: DigitRules,
+    mut on_line_without_digit: impl FnMut(LineDiagnostic<'_>),
+) -> Result<CalibrationSum, CalibrationError> {
+    let mut sum = CalibrationSum::default();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(sum);
+        }
+        sum.lines_read += 1;
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        if trimmed.is_empty() {
+            continue;
+        }
+        match calibration_value(trimmed, rules) {
+            Some(value) => {
+                sum.total = sum
+                    .total
+                    .checked_add(value)
+                    .ok_or(CalibrationError::Overflow {
+                        line_number: sum.lines_read,
+                    })?;
             }
-            return format!("{}{}", numbers.first().unwrap(), numbers.last().unwrap())
-                .parse::<i32>()
-                .unwrap();
-        })
-        .sum();
+            None => {
+                sum.lines_without_digit += 1;
+                on_line_without_digit(LineDiagnostic {
+                    line_number: sum.lines_read,
+                    line: trimmed,
+                });
+            }
+        }
+    }
+}
+
+fn sum_input(rules: DigitRules) -> CalibrationSum {
+    let reader = BufReader::new(File::open("input/day01").unwrap());
+    sum_calibration_values(reader, rules, |diagnostic| {
+        eprintln!(
+            "line {} has no digit: {}",
+            diagnostic.line_number, diagnostic.line
+        )
+    })
+    .unwrap()
+}
+
+fn solve_part_one() -> u64 {
+    sum_input(DigitRules::NumericOnly).total
+}
+
+fn solve_part_two() -> u64 {
+    sum_input(DigitRules::NumericAndWords).total
 }
 
 pub fn solve() {
@@ -1,127 +1,229 @@
-enum Color {
-    Red,
-    Green,
-    Blue,
-}
-
-struct Cube {
-    color: Color,
-    count: i32,
-}
-
-struct Set {
-    cubes: Vec<Cube>,
-}
-
-struct Game {
-    number: i32,
-    sets: Vec<Set>,
-}
-
-fn parse_set(set_string: &str) -> Set {
-    return Set {
-        cubes: set_string
-            .split(",")
-            .map(|colored_cubes| {
-                let split_colored_cubes = colored_cubes.split(" ").collect::<Vec<&str>>();
-                return Cube {
-                    color: match split_colored_cubes[2] {
-                        "red" => Color::Red,
-                        "green" => Color::Green,
-                        "blue" => Color::Blue,
-                        _ => panic!("unknown color {}", split_colored_cubes[2]),
-                    },
-                    count: split_colored_cubes[1].parse::<i32>().unwrap(),
-                };
-            })
-            .collect::<Vec<Cube>>(),
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CubeSet {
+    pub cubes: BTreeMap<String, u32>,
+}
+
+pub type Bag = CubeSet;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub number: u32,
+    pub sets: Vec<CubeSet>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseGameError {
+    pub line: String,
+    pub reason: &'static str,
+}
+
+impl CubeSet {
+    pub fn count(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.cubes.values().sum()
+    }
+
+    pub fn power(&self) -> u64 {
+        self.cubes.values().map(|count| *count as u64).product()
+    }
+
+    pub fn contains(&self, other: &CubeSet) -> bool {
+        other
+            .cubes
+            .iter()
+            .all(|(color, count)| self.count(color) >= *count)
+    }
+}
+
+impl<S: Into<String>, const N: usize> From<[(S, u32); N]> for CubeSet {
+    fn from(cubes: [(S, u32); N]) -> Self {
+        CubeSet {
+            cubes: cubes
+                .into_iter()
+                .map(|(color, count)| (color.into(), count))
+                .collect(),
+        }
+    }
+}
+
+impl Game {
+    pub fn is_possible_with(&self, bag: &Bag) -> bool {
+        self.sets.iter().all(|set| bag.contains(set))
+    }
+
+    pub fn minimum_bag(&self) -> Bag {
+        let mut bag = Bag::default();
+        for set in &self.sets {
+            for (color, count) in &set.cubes {
+                let heighest = bag.cubes.entry(color.clone()).or_insert(0);
+                *heighest = (*heighest).max(*count);
+            }
+        }
+        bag
+    }
+}
+
+fn parse_set(set_string: &str, line: &str) -> Result<CubeSet, ParseGameError> {
+    let error = |reason| ParseGameError {
+        line: line.to_string(),
+        reason,
     };
+    let mut set = CubeSet::default();
+    for colored_cubes in set_string.split(",") {
+        let mut split_colored_cubes = colored_cubes.split_whitespace();
+        let count = split_colored_cubes
+            .next()
+            .ok_or(error("missing cube count"))?
+            .parse::<u32>()
+            .map_err(|_| error("cube count is not a number"))?;
+        let color = split_colored_cubes
+            .next()
+            .ok_or(error("missing cube color"))?;
+        *set.cubes.entry(color.to_string()).or_insert(0) += count;
+    }
+    Ok(set)
 }
 
-fn parse_all_games() -> Vec<Game> {
-    return std::fs::read_to_string("input/day02")
-        .unwrap()
+pub fn parse_game(line: &str) -> Result<Game, ParseGameError> {
+    let error = |reason| ParseGameError {
+        line: line.to_string(),
+        reason,
+    };
+    let (game_label, game_sets) = line.split_once(":").ok_or(error("missing ':'"))?;
+    let number = game_label
+        .trim()
+        .strip_prefix("Game ")
+        .ok_or(error("missing 'Game' label"))?
+        .parse::<u32>()
+        .map_err(|_| error("game number is not a number"))?;
+    let sets = game_sets
+        .split(";")
+        .map(|set| parse_set(set, line))
+        .collect::<Result<Vec<CubeSet>, ParseGameError>>()?;
+    Ok(Game { number, sets })
+}
+
+pub fn parse_games(input: &str) -> Result<Vec<Game>, ParseGameError> {
+    input
         .split("\n")
         .filter(|line| !line.is_empty())
-        .map(|line| {
-            let split_line = line.split(":").collect::<Vec<&str>>();
-            let game_number = split_line[0].split(" ").collect::<Vec<&str>>()[1]
-                .parse::<i32>()
-                .unwrap();
-            let game_sets = split_line[1]
-                .split(";")
-                .map(|set| parse_set(set))
-                .collect::<Vec<Set>>();
-            return Game {
-                number: game_number,
-                sets: game_sets,
-            };
-        })
-        .collect::<Vec<Game>>();
+        .map(parse_game)
+        .collect()
 }
 
-fn solve_part_one() -> i32 {
-    return parse_all_games()
-        .into_iter()
-        .map(|game| {
-            let impossible_game = game.sets.into_iter().any(|set| {
-                for cube in set.cubes {
-                    match cube.color {
-                        Color::Red => {
-                            if cube.count > 12 {
-                                return true;
-                            }
-                        }
-                        Color::Green => {
-                            if cube.count > 13 {
-                                return true;
-                            }
-                        }
-                        Color::Blue => {
-                            if cube.count > 14 {
-                                return true;
-                            }
-                        }
-                    }
-                }
-                return false;
-            });
-            return if impossible_game { 0 } else { game.number };
+pub fn possible_games<'a>(games: &'a [Game], bag: &Bag) -> Vec<&'a Game> {
+    games
+        .iter()
+        .filter(|game| game.is_possible_with(bag))
+        .collect()
+}
+
+fn ln_binomial(n: u32, k: u32) -> f64 {
+    (0..k)
+        .map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln())
+        .sum()
+}
+
+pub fn log_likelihood(games: &[Game], bag: &Bag) -> f64 {
+    let total = bag.total();
+    games
+        .iter()
+        .flat_map(|game| game.sets.iter())
+        .map(|set| {
+            if !bag.contains(set) {
+                return f64::NEG_INFINITY;
+            }
+            let colors = set
+                .cubes
+                .iter()
+                .map(|(color, count)| ln_binomial(bag.count(color), *count))
+                .sum::<f64>();
+            colors - ln_binomial(total, set.total())
         })
-        .sum();
+        .sum()
+}
+
+fn marginal_gain(games: &[Game], color: &str, count: u32) -> f64 {
+    games
+        .iter()
+        .flat_map(|game| game.sets.iter())
+        .map(|set| set.count(color))
+        .filter(|drawn| *drawn > 0)
+        .map(|drawn| ((count + 1) as f64).ln() - ((count + 1 - drawn) as f64).ln())
+        .sum()
+}
+
+fn add_most_likely_cube(games: &[Game], bag: &mut Bag) {
+    let color = bag
+        .cubes
+        .iter()
+        .map(|(color, count)| (color, marginal_gain(games, color, *count)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(color, _)| color.clone())
+        .unwrap();
+    *bag.cubes.get_mut(&color).unwrap() += 1;
+}
+
+fn minimum_bag_for_all(games: &[Game]) -> Bag {
+    let mut bag = Bag::default();
+    for game in games {
+        for (color, count) in game.minimum_bag().cubes {
+            let heighest = bag.cubes.entry(color).or_insert(0);
+            *heighest = (*heighest).max(count);
+        }
+    }
+    bag
+}
+
+pub fn most_likely_bag(games: &[Game], total_cubes: u32) -> Option<Bag> {
+    let mut bag = minimum_bag_for_all(games);
+    if bag.cubes.is_empty() || bag.total() > total_cubes {
+        return None;
+    }
+    while bag.total() < total_cubes {
+        add_most_likely_cube(games, &mut bag);
+    }
+    Some(bag)
 }
 
-fn solve_part_two() -> i32 {
-    return parse_all_games()
+pub fn most_likely_bag_up_to(games: &[Game], max_total_cubes: u32) -> Option<Bag> {
+    let mut bag = minimum_bag_for_all(games);
+    if bag.cubes.is_empty() || bag.total() > max_total_cubes {
+        return None;
+    }
+    let mut best = (log_likelihood(games, &bag), bag.clone());
+    while bag.total() < max_total_cubes {
+        add_most_likely_cube(games, &mut bag);
+        let likelihood = log_likelihood(games, &bag);
+        if likelihood > best.0 {
+            best = (likelihood, bag.clone());
+        }
+    }
+    Some(best.1)
+}
+
+fn parse_all_games() -> Vec<Game> {
+    parse_games(&std::fs::read_to_string("input/day02").unwrap()).unwrap()
+}
+
+fn solve_part_one() -> u32 {
+    let bag = Bag::from([("red", 12), ("green", 13), ("blue", 14)]);
+    possible_games(&parse_all_games(), &bag)
         .into_iter()
-        .map(|game| {
-            let mut heighest_red = 0;
-            let mut heighest_green = 0;
-            let mut heighest_blue = 0;
-            game.sets.into_iter().for_each(|set| {
-                for cube in set.cubes {
-                    match cube.color {
-                        Color::Red => {
-                            if cube.count > heighest_red {
-                                heighest_red = cube.count;
-                            }
-                        }
-                        Color::Green => {
-                            if cube.count > heighest_green {
-                                heighest_green = cube.count;
-                            }
-                        }
-                        Color::Blue => {
-                            if cube.count > heighest_blue {
-                                heighest_blue = cube.count;
-                            }
-                        }
-                    }
-                }
-            });
-            return heighest_red * heighest_green * heighest_blue;
-        })
-        .sum();
+        .map(|game| game.number)
+        .sum()
+}
+
+fn solve_part_two() -> u64 {
+    parse_all_games()
+        .iter()
+        .map(|game| game.minimum_bag().power())
+        .sum()
 }
 
 pub fn solve() {
@@ -1,136 +1,201 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-struct NumberLocation {
-    line_number: usize,
-    from: usize,
-    to: usize,
+pub type Position = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchematicNumber {
+    pub value: u64,
+    pub cells: Vec<Position>,
 }
 
-struct EngineSchematic {
-    number_locations: Vec<(i32, NumberLocation)>,
-    symbols: HashMap<(usize, usize), char>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gear {
+    pub position: Position,
+    pub symbol: char,
+    pub numbers: Vec<usize>,
 }
 
-fn parse_engine_schematic() -> EngineSchematic {
-    let mut symbols: HashMap<(usize, usize), char> = HashMap::new();
-    let mut number_locations: Vec<(i32, NumberLocation)> = Vec::new();
-    std::fs::read_to_string("input/day03")
-        .unwrap()
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| line.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>()
-        .into_iter()
-        .enumerate()
-        .for_each(|(line_number, line)| {
-            let mut parsed_number_string = String::from("");
-            let mut number_from_index = 0;
-            line.clone()
-                .into_iter()
-                .enumerate()
-                .for_each(|(char_index, char)| {
-                    if char.is_numeric() {
-                        if parsed_number_string == "" {
-                            number_from_index = char_index;
-                        }
-                        parsed_number_string.push(char);
-                    }
-                    if parsed_number_string != "" && !char.is_numeric()
-                        || char_index == line.len() - 1 && parsed_number_string != ""
-                    {
-                        let number_to_index = if char_index == line.len() - 1 {
-                            char_index
-                        } else {
-                            char_index - 1
-                        };
-                        let _ = number_locations.push((
-                            parsed_number_string.parse::<i32>().unwrap(),
-                            NumberLocation {
-                                line_number,
-                                from: number_from_index,
-                                to: number_to_index,
-                            },
-                        ));
-                        parsed_number_string = String::from("");
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSchematicError {
+    NumberTooLarge { position: Position },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberWrapping {
+    WithinLine,
+    AcrossLineEnds,
+}
+
+pub struct EngineSchematic {
+    lines: Vec<Vec<char>>,
+    pub numbers: Vec<SchematicNumber>,
+    pub symbols: BTreeMap<Position, char>,
+    number_at: HashMap<Position, usize>,
+}
+
+impl EngineSchematic {
+    pub fn parse(input: &str, wrapping: NumberWrapping) -> Result<Self, ParseSchematicError> {
+        let lines = input
+            .split("\n")
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().collect::<Vec<char>>())
+            .collect::<Vec<Vec<char>>>();
+        let mut numbers: Vec<SchematicNumber> = Vec::new();
+        let mut symbols: BTreeMap<Position, char> = BTreeMap::new();
+        let mut current: Option<SchematicNumber> = None;
+        for (line_number, line) in lines.iter().enumerate() {
+            if wrapping == NumberWrapping::WithinLine {
+                numbers.extend(current.take());
+            }
+            for (char_index, char) in line.iter().enumerate() {
+                match char.to_digit(10) {
+                    Some(digit) => {
+                        let number = current.get_or_insert(SchematicNumber {
+                            value: 0,
+                            cells: Vec::new(),
+                        });
+                        number.value = number
+                            .value
+                            .checked_mul(10)
+                            .and_then(|value| value.checked_add(digit as u64))
+                            .ok_or(ParseSchematicError::NumberTooLarge {
+                                position: (line_number, char_index),
+                            })?;
+                        number.cells.push((line_number, char_index));
                     }
-                    if !char.is_numeric() && char != '.' {
-                        symbols.insert((line_number, char_index), char);
+                    None => {
+                        numbers.extend(current.take());
+                        if *char != '.' {
+                            symbols.insert((line_number, char_index), *char);
+                        }
                     }
-                })
-        });
-    return EngineSchematic {
-        number_locations,
-        symbols,
-    };
-}
+                }
+            }
+        }
+        numbers.extend(current.take());
+        let number_at = numbers
+            .iter()
+            .enumerate()
+            .flat_map(|(index, number)| number.cells.iter().map(move |cell| (*cell, index)))
+            .collect::<HashMap<Position, usize>>();
+        Ok(EngineSchematic {
+            lines,
+            numbers,
+            symbols,
+            number_at,
+        })
+    }
+
+    fn neighbours(&self, (y, x): Position) -> impl Iterator<Item = Position> + '_ {
+        (y.saturating_sub(1)..=y + 1)
+            .flat_map(move |ny| (x.saturating_sub(1)..=x + 1).map(move |nx| (ny, nx)))
+            .filter(move |&(ny, nx)| {
+                (ny, nx) != (y, x) && ny < self.lines.len() && nx < self.lines[ny].len()
+            })
+    }
 
-fn is_adjecent_to_symbol(
-    number_location: &NumberLocation,
-    symbols: HashMap<(usize, usize), char>,
-) -> bool {
-    let mut surrounding_positions: Vec<(usize, usize)> = Vec::new();
-    let from_line_number = if number_location.line_number == 0 {
-        0
-    } else {
-        number_location.line_number - 1
-    };
-    let from_char_index = if number_location.from == 0 {
-        0
-    } else {
-        number_location.from - 1
-    };
-    for line_number in from_line_number..number_location.line_number + 2 {
-        for char_index in from_char_index..number_location.to + 2 {
-            surrounding_positions.push((line_number, char_index));
+    pub fn numbers_adjacent_to(&self, position: Position) -> Vec<usize> {
+        self.neighbours(position)
+            .filter_map(|neighbour| self.number_at.get(&neighbour).copied())
+            .collect::<BTreeSet<usize>>()
+            .into_iter()
+            .collect()
+    }
+
+    pub fn symbols_adjacent_to(&self, number_index: usize) -> Vec<(Position, char)> {
+        self.numbers[number_index]
+            .cells
+            .iter()
+            .flat_map(|cell| self.neighbours(*cell))
+            .filter_map(|neighbour| {
+                self.symbols
+                    .get(&neighbour)
+                    .map(|symbol| (neighbour, *symbol))
+            })
+            .collect::<BTreeMap<Position, char>>()
+            .into_iter()
+            .collect()
+    }
+
+    pub fn part_numbers(&self) -> Vec<usize> {
+        (0..self.numbers.len())
+            .filter(|index| !self.symbols_adjacent_to(*index).is_empty())
+            .collect()
+    }
+
+    pub fn gears(&self, symbol: char, adjacent_numbers: usize) -> Vec<Gear> {
+        self.symbols
+            .iter()
+            .filter(|(_, candidate)| **candidate == symbol)
+            .map(|(position, _)| Gear {
+                position: *position,
+                symbol,
+                numbers: self.numbers_adjacent_to(*position),
+            })
+            .filter(|gear| gear.numbers.len() == adjacent_numbers)
+            .collect()
+    }
+
+    pub fn gear_ratio(&self, gear: &Gear) -> u64 {
+        gear.numbers
+            .iter()
+            .map(|index| self.numbers[*index].value)
+            .product()
+    }
+
+    pub fn render(&self, highlighted: &HashSet<Position>) -> String {
+        let mut rendered = String::new();
+        for (line_number, line) in self.lines.iter().enumerate() {
+            for (char_index, char) in line.iter().enumerate() {
+                if highlighted.contains(&(line_number, char_index)) {
+                    rendered.push_str(&format!("\x1b[1;33m{}\x1b[0m", char));
+                } else {
+                    rendered.push(*char);
+                }
+            }
+            rendered.push('\n');
         }
+        rendered
+    }
+
+    pub fn render_gears(&self, gears: &[Gear]) -> String {
+        let highlighted = gears
+            .iter()
+            .flat_map(|gear| {
+                gear.numbers
+                    .iter()
+                    .flat_map(|index| self.numbers[*index].cells.iter().copied())
+                    .chain(std::iter::once(gear.position))
+            })
+            .collect::<HashSet<Position>>();
+        self.render(&highlighted)
     }
-    return surrounding_positions
-        .into_iter()
-        .any(|(y, x)| symbols.contains_key(&(y, x)));
 }
 
-fn gear_sum(x: usize, y: usize, number_locations: &Vec<(i32, NumberLocation)>) -> i32 {
-    let from_line_number = if y == 0 { 0 } else { y - 1 };
-    let from_char_index = if x == 0 { 0 } else { x - 1 };
-    let adjecent_numbers = number_locations
-        .into_iter()
-        .filter(|(_, number_location)| {
-            return (from_line_number..y + 2).contains(&number_location.line_number)
-                && ((from_char_index..x + 2).contains(&number_location.from)
-                    || (from_char_index..x + 2).contains(&number_location.to));
-        })
-        .map(|(number, _)| number)
-        .collect::<Vec<&i32>>();
-    return if adjecent_numbers.len() == 2 {
-        adjecent_numbers[0] * adjecent_numbers[1]
-    } else {
-        0
-    };
+fn parse_engine_schematic() -> EngineSchematic {
+    EngineSchematic::parse(
+        &std::fs::read_to_string("input/day03").unwrap(),
+        NumberWrapping::WithinLine,
+    )
+    .unwrap()
 }
 
-fn solve_part_one() -> i32 {
+fn solve_part_one() -> u64 {
     let engine_schematic = parse_engine_schematic();
-    return engine_schematic
-        .number_locations
-        .iter()
-        .map(|(number, number_location)| {
-            return if is_adjecent_to_symbol(number_location, engine_schematic.symbols.clone()) {
-                *number
-            } else {
-                0
-            };
-        })
-        .sum::<i32>();
+    engine_schematic
+        .part_numbers()
+        .into_iter()
+        .map(|index| engine_schematic.numbers[index].value)
+        .sum()
 }
 
-fn solve_part_two() -> i32 {
+fn solve_part_two() -> u64 {
     let engine_schematic = parse_engine_schematic();
-    return engine_schematic
-        .symbols
+    engine_schematic
+        .gears('*', 2)
         .iter()
-        .filter(|(_, symbol)| symbol.to_owned().to_owned() == '*')
-        .map(|((y, x), _)| gear_sum(*x, *y, &engine_schematic.number_locations))
-        .sum::<i32>();
+        .map(|gear| engine_schematic.gear_ratio(gear))
+        .sum()
 }
 
 pub fn solve() {
@@ -1,78 +1,86 @@
-use std::collections::HashMap;
+use num_bigint::BigUint;
 
-fn recursive_scratch(card_number: i32, scratch_cards: &HashMap<i32, Vec<i32>>) -> i32 {
-    if !scratch_cards.contains_key(&card_number) {
-        panic!("card not found in scratch cards {}", card_number);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScratchCard {
+    pub number: u32,
+    pub matches: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardCopies {
+    pub number: u32,
+    pub copies: BigUint,
+}
+
+impl ScratchCard {
+    pub fn score(&self) -> BigUint {
+        if self.matches == 0 {
+            BigUint::ZERO
+        } else {
+            BigUint::from(1u32) << (self.matches - 1)
+        }
     }
-    return 1 + scratch_cards[&card_number]
-        .clone()
-        .into_iter()
-        .map(|number| recursive_scratch(number, &scratch_cards))
-        .sum::<i32>();
 }
 
-fn solve_part_two() -> i32 {
-    let mut scratch_cards = HashMap::new();
-    let my_cards = std::fs::read_to_string("input/day04")
-        .unwrap()
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let split_line = line.split(":").collect::<Vec<&str>>();
-            let card_number = split_line[0]
-                .split(" ")
-                .filter(|part| !part.is_empty())
-                .collect::<Vec<&str>>()[1]
-                .parse::<i32>()
-                .unwrap();
-            let winning_numbers = split_line[1].split("|").collect::<Vec<&str>>()[0]
-                .split(" ")
-                .filter(|number| !number.is_empty())
-                .collect::<Vec<&str>>();
-            let my_matches = split_line[1].split("|").collect::<Vec<&str>>()[1]
-                .split(" ")
-                .filter(|number| !number.is_empty() && winning_numbers.contains(number))
-                .collect::<Vec<&str>>()
-                .len();
-            scratch_cards.insert(
-                card_number,
-                (card_number + 1..card_number + my_matches as i32 + 1).collect::<Vec<i32>>(),
-            );
-            return card_number;
-        })
-        .collect::<Vec<i32>>();
+fn parse_numbers(numbers: &str) -> Vec<&str> {
+    numbers
+        .split(" ")
+        .filter(|number| !number.is_empty())
+        .collect::<Vec<&str>>()
+}
 
-    return my_cards
+pub fn parse_scratch_card(line: &str) -> ScratchCard {
+    let (card_label, card_info) = line.split_once(":").unwrap();
+    let number = parse_numbers(card_label)[1].parse::<u32>().unwrap();
+    let (winning_numbers, my_numbers) = card_info.split_once("|").unwrap();
+    let winning_numbers = parse_numbers(winning_numbers);
+    let matches = parse_numbers(my_numbers)
         .into_iter()
-        .map(|card_number| recursive_scratch(card_number, &scratch_cards))
-        .sum();
+        .filter(|number| winning_numbers.contains(number))
+        .count();
+    ScratchCard { number, matches }
 }
 
-fn solve_part_one() -> i32 {
-    return std::fs::read_to_string("input/day04")
+pub fn scratch_cascade(scratch_cards: &[ScratchCard]) -> Vec<CardCopies> {
+    let mut copies = vec![BigUint::from(1u32); scratch_cards.len()];
+    for (index, scratch_card) in scratch_cards.iter().enumerate() {
+        let won_until = (index + 1 + scratch_card.matches).min(scratch_cards.len());
+        let (won_from, won) = copies.split_at_mut(index + 1);
+        for won_copies in &mut won[..won_until - index - 1] {
+            *won_copies += &won_from[index];
+        }
+    }
+    scratch_cards
+        .iter()
+        .zip(copies)
+        .map(|(scratch_card, copies)| CardCopies {
+            number: scratch_card.number,
+            copies,
+        })
+        .collect()
+}
+
+fn parse_my_cards() -> Vec<ScratchCard> {
+    std::fs::read_to_string("input/day04")
         .unwrap()
         .split("\n")
         .filter(|line| !line.is_empty())
-        .map(|line| {
-            let card_info = line.split(":").collect::<Vec<&str>>()[1];
-            let winning_numbers = card_info.split("|").collect::<Vec<&str>>()[0]
-                .split(" ")
-                .filter(|number| !number.is_empty())
-                .collect::<Vec<&str>>();
-            let mut my_score = 0;
-            card_info.split("|").collect::<Vec<&str>>()[1]
-                .split(" ")
-                .filter(|number| !number.is_empty() && winning_numbers.contains(number))
-                .for_each(|_| {
-                    if my_score == 0 {
-                        my_score += 1
-                    } else {
-                        my_score *= 2
-                    }
-                });
-            return my_score;
-        })
-        .sum();
+        .map(parse_scratch_card)
+        .collect()
+}
+
+fn solve_part_one() -> BigUint {
+    parse_my_cards()
+        .iter()
+        .map(|scratch_card| scratch_card.score())
+        .sum()
+}
+
+fn solve_part_two() -> BigUint {
+    scratch_cascade(&parse_my_cards())
+        .into_iter()
+        .map(|card_copies| card_copies.copies)
+        .sum()
 }
 
 pub fn solve() {
@@ -1,14 +1,350 @@
-struct RangeMapping {
-    from: i64,
-    to: i64,
-    range: i64,
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeMapping {
+    pub from: i64,
+    pub to: i64,
+    pub range: i64,
 }
 
-type RangeMappings = Vec<RangeMapping>;
+pub type RangeMappings = Vec<RangeMapping>;
 
-struct ParsedInput {
-    seeds: Vec<i64>,
+pub struct ParsedInput {
+    pub seeds: Vec<i64>,
+    pub categories: Vec<String>,
     all_range_mappings: Vec<RangeMappings>,
+    layer_maps: Vec<IntervalMap>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeMappingError {
+    NegativeRange(RangeMapping),
+    Overflow(RangeMapping),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedLocation {
+    pub seed: i64,
+    pub location: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalMap {
+    pieces: Vec<(i64, i128)>,
+}
+
+impl RangeMapping {
+    pub fn source(&self) -> Result<Range<i64>, RangeMappingError> {
+        if self.range < 0 {
+            return Err(RangeMappingError::NegativeRange(*self));
+        }
+        let source_end = self.from.checked_add(self.range);
+        let destination_end = self.to.checked_add(self.range);
+        match (source_end, destination_end) {
+            (Some(source_end), Some(_)) => Ok(self.from..source_end),
+            _ => Err(RangeMappingError::Overflow(*self)),
+        }
+    }
+}
+
+impl IntervalMap {
+    pub fn identity() -> Self {
+        IntervalMap {
+            pieces: vec![(i64::MIN, 0)],
+        }
+    }
+
+    pub fn from_range_mappings(range_mappings: &[RangeMapping]) -> Result<Self, RangeMappingError> {
+        let mut interval_map = IntervalMap::identity();
+        for range_mapping in range_mappings.iter().rev() {
+            interval_map.overlay(
+                range_mapping.source()?,
+                range_mapping.to as i128 - range_mapping.from as i128,
+            );
+        }
+        Ok(interval_map)
+    }
+
+    fn piece_index(&self, value: i128) -> usize {
+        self.pieces
+            .partition_point(|(start, _)| (*start as i128) <= value)
+            .max(1)
+            - 1
+    }
+
+    fn piece_end(&self, index: usize) -> i128 {
+        match self.pieces.get(index + 1) {
+            Some((next_start, _)) => *next_start as i128 - 1,
+            None => i64::MAX as i128,
+        }
+    }
+
+    fn split_at(&mut self, value: i64) {
+        let index = self.piece_index(value as i128);
+        if self.pieces[index].0 != value {
+            self.pieces.insert(index + 1, (value, self.pieces[index].1));
+        }
+    }
+
+    fn overlay(&mut self, range: Range<i64>, offset: i128) {
+        if range.is_empty() {
+            return;
+        }
+        self.split_at(range.start);
+        self.split_at(range.end);
+        for piece in self.pieces.iter_mut() {
+            if range.contains(&piece.0) {
+                piece.1 = offset;
+            }
+        }
+        self.normalize();
+    }
+
+    fn normalize(&mut self) {
+        self.pieces.dedup_by(|next, previous| next.1 == previous.1);
+    }
+
+    pub fn pieces(&self) -> impl Iterator<Item = (i64, i64, i128)> + '_ {
+        (0..self.pieces.len()).map(|index| {
+            let (start, offset) = self.pieces[index];
+            (start, self.piece_end(index) as i64, offset)
+        })
+    }
+
+    pub fn apply(&self, value: i64) -> i64 {
+        (value as i128 + self.pieces[self.piece_index(value as i128)].1) as i64
+    }
+
+    pub fn then(&self, next: &IntervalMap) -> IntervalMap {
+        let mut pieces = Vec::new();
+        for index in 0..self.pieces.len() {
+            let (start, offset) = self.pieces[index];
+            let image_end = self.piece_end(index) + offset;
+            let mut image_start = start as i128 + offset;
+            let mut next_index = next.piece_index(image_start);
+            loop {
+                pieces.push((
+                    (image_start - offset) as i64,
+                    offset + next.pieces[next_index].1,
+                ));
+                let next_end = match next.pieces.get(next_index + 1) {
+                    Some((next_start, _)) => *next_start as i128 - 1,
+                    None => i128::MAX,
+                };
+                if next_end >= image_end {
+                    break;
+                }
+                image_start = next_end + 1;
+                next_index += 1;
+            }
+        }
+        let mut interval_map = IntervalMap { pieces };
+        interval_map.normalize();
+        interval_map
+    }
+
+    pub fn image_of_range(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let mut image = self
+            .pieces()
+            .filter(|(start, end, _)| *start < range.end && *end >= range.start)
+            .map(|(start, end, offset)| {
+                let image_start = start.max(range.start) as i128 + offset;
+                let image_end = end.min(range.end - 1) as i128 + offset + 1;
+                image_start as i64..i64::try_from(image_end).unwrap_or(i64::MAX)
+            })
+            .collect::<Vec<Range<i64>>>();
+        merge_ranges(&mut image);
+        image
+    }
+
+    pub fn preimage(&self, value: i64) -> Vec<i64> {
+        self.pieces()
+            .map(|(start, end, offset)| (start, end, value as i128 - offset))
+            .filter(|(start, end, candidate)| (*start as i128..=*end as i128).contains(candidate))
+            .map(|(_, _, candidate)| candidate as i64)
+            .collect()
+    }
+
+    pub fn preimage_of_range(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let mut preimage = self
+            .pieces()
+            .filter_map(|(start, end, offset)| {
+                let from = (start as i128).max(range.start as i128 - offset);
+                let to = (end as i128).min(range.end as i128 - 1 - offset);
+                if from > to {
+                    return None;
+                }
+                Some(from as i64..(to + 1) as i64)
+            })
+            .collect::<Vec<Range<i64>>>();
+        merge_ranges(&mut preimage);
+        preimage
+    }
+
+    pub fn inverse(&self) -> Option<IntervalMap> {
+        let mut images = self
+            .pieces()
+            .map(|(start, end, offset)| (start as i128 + offset, end as i128 + offset, offset))
+            .collect::<Vec<(i128, i128, i128)>>();
+        images.sort();
+        let mut expected_start = i64::MIN as i128;
+        for (image_start, image_end, _) in &images {
+            if *image_start != expected_start {
+                return None;
+            }
+            expected_start = image_end + 1;
+        }
+        if expected_start != i64::MAX as i128 + 1 {
+            return None;
+        }
+        let mut inverse = IntervalMap {
+            pieces: images
+                .into_iter()
+                .map(|(image_start, _, offset)| (image_start as i64, -offset))
+                .collect(),
+        };
+        inverse.normalize();
+        Some(inverse)
+    }
+}
+
+impl fmt::Display for IntervalMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bound = |value: i64| match value {
+            i64::MIN => String::from("-inf"),
+            i64::MAX => String::from("+inf"),
+            _ => value.to_string(),
+        };
+        for (start, end, offset) in self.pieces() {
+            if offset == 0 {
+                writeln!(f, "[{}, {}] -> identity", bound(start), bound(end))?;
+            } else {
+                writeln!(
+                    f,
+                    "[{}, {}] -> [{}, {}] ({:+})",
+                    bound(start),
+                    bound(end),
+                    bound((start as i128 + offset) as i64),
+                    bound((end as i128 + offset) as i64),
+                    offset
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn merge_ranges(ranges: &mut Vec<Range<i64>>) {
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<i64>> = Vec::new();
+    for range in ranges.drain(..) {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    *ranges = merged;
+}
+
+impl ParsedInput {
+    pub fn new(
+        seeds: Vec<i64>,
+        categories: Vec<String>,
+        all_range_mappings: Vec<RangeMappings>,
+    ) -> Result<Self, RangeMappingError> {
+        let layer_maps = all_range_mappings
+            .iter()
+            .map(|range_mappings| IntervalMap::from_range_mappings(range_mappings))
+            .collect::<Result<Vec<IntervalMap>, RangeMappingError>>()?;
+        Ok(ParsedInput {
+            seeds,
+            categories,
+            all_range_mappings,
+            layer_maps,
+        })
+    }
+
+    pub fn all_range_mappings(&self) -> &[RangeMappings] {
+        &self.all_range_mappings
+    }
+
+    pub fn layer_maps(&self) -> &[IntervalMap] {
+        &self.layer_maps
+    }
+
+    pub fn seed_to_location(&self) -> IntervalMap {
+        self.layer_maps()
+            .iter()
+            .fold(IntervalMap::identity(), |composed, layer| {
+                composed.then(layer)
+            })
+    }
+
+    pub fn seed_ranges(&self) -> Vec<Range<i64>> {
+        self.seeds
+            .chunks_exact(2)
+            .map(|pair| pair[0]..pair[0] + pair[1])
+            .collect()
+    }
+
+    pub fn category_index(&self, category: &str) -> Option<usize> {
+        self.categories
+            .iter()
+            .position(|candidate| candidate == category)
+    }
+
+    pub fn seed_to_category(&self, category: &str) -> Option<IntervalMap> {
+        let category_index = self.category_index(category)?;
+        Some(
+            self.layer_maps()
+                .iter()
+                .take(category_index)
+                .fold(IntervalMap::identity(), |composed, layer| {
+                    composed.then(layer)
+                }),
+        )
+    }
+
+    pub fn seeds_producing(&self, category: &str, value: i64) -> Option<Vec<i64>> {
+        let mut seeds = self.seed_to_category(category)?.preimage(value);
+        seeds.sort();
+        Some(seeds)
+    }
+
+    pub fn seed_ranges_producing(
+        &self,
+        category: &str,
+        range: Range<i64>,
+    ) -> Option<Vec<Range<i64>>> {
+        Some(self.seed_to_category(category)?.preimage_of_range(range))
+    }
+
+    pub fn seed_with_minimum_location(&self, seed_ranges: &[Range<i64>]) -> Option<SeedLocation> {
+        let seed_to_location = self.seed_to_location();
+        seed_ranges
+            .iter()
+            .flat_map(|seed_range| {
+                seed_to_location
+                    .pieces()
+                    .filter(|(start, end, _)| *start < seed_range.end && *end >= seed_range.start)
+                    .map(|(start, _, offset)| {
+                        let seed = start.max(seed_range.start);
+                        SeedLocation {
+                            seed,
+                            location: (seed as i128 + offset) as i64,
+                        }
+                    })
+                    .collect::<Vec<SeedLocation>>()
+            })
+            .min_by_key(|seed_location| (seed_location.location, seed_location.seed))
+    }
 }
 
 fn parse_range_mappings(input: &str) -> RangeMappings {
@@ -24,140 +360,318 @@ fn parse_range_mappings(input: &str) -> RangeMappings {
                 .split(" ")
                 .map(|s| s.parse::<i64>().unwrap())
                 .collect::<Vec<i64>>();
-            return RangeMapping {
+            RangeMapping {
                 from: numbers[1],
                 to: numbers[0],
                 range: numbers[2],
-            };
+            }
         })
         .collect::<RangeMappings>()
 }
 
-fn parse_input() -> ParsedInput {
-    let input = std::fs::read_to_string("input/day05").unwrap();
+pub fn parse_almanac(input: &str) -> Result<ParsedInput, RangeMappingError> {
     let mut input_parts = input
         .split("\n\n")
         .filter(|line| !line.is_empty())
         .collect::<Vec<&str>>();
     let seeds = input_parts[0].split("seeds: ").collect::<Vec<&str>>()[1]
-        .split(" ")
+        .split_whitespace()
         .map(|s| s.parse::<i64>().unwrap())
         .collect::<Vec<i64>>();
     input_parts.remove(0);
+    let mut categories = vec![String::from("seed")];
+    categories.extend(input_parts.iter().map(|part| {
+        let header = part.split_whitespace().next().unwrap();
+        header.rsplit("-to-").next().unwrap().to_string()
+    }));
     let all_range_mappings = input_parts
         .into_iter()
-        .map(|s| parse_range_mappings(s))
+        .map(parse_range_mappings)
         .collect::<Vec<RangeMappings>>();
-    ParsedInput {
-        seeds,
-        all_range_mappings,
+    ParsedInput::new(seeds, categories, all_range_mappings)
+}
+
+fn parse_input() -> ParsedInput {
+    parse_almanac(&std::fs::read_to_string("input/day05").unwrap()).unwrap()
+}
+
+pub fn map_value(range_mappings: &[RangeMapping], value: i64) -> i64 {
+    for range_mapping in range_mappings {
+        let offset = value as i128 - range_mapping.from as i128;
+        if (0..range_mapping.range as i128).contains(&offset) {
+            return (range_mapping.to as i128 + offset) as i64;
+        }
     }
+    value
 }
 
 fn solve_part_one() -> i64 {
     let parsed_input = parse_input();
-    return parsed_input
+    parsed_input
         .seeds
-        .into_iter()
+        .iter()
         .map(|seed| {
-            let mut mapped_value: i64 = seed;
             parsed_input
-                .all_range_mappings
+                .all_range_mappings()
                 .iter()
-                .for_each(|range_mappings| {
-                    for range_mapping in range_mappings {
-                        if mapped_value >= range_mapping.from
-                            && mapped_value < range_mapping.from + range_mapping.range
-                        {
-                            mapped_value = range_mapping.to + (mapped_value - range_mapping.from);
-                            break;
-                        }
-                    }
-                });
-            return mapped_value;
+                .fold(*seed, |mapped_value, range_mappings| {
+                    map_value(range_mappings, mapped_value)
+                })
         })
-        .collect::<Vec<i64>>()
-        .into_iter()
         .min()
-        .unwrap();
+        .unwrap()
 }
 
-fn split_seed_range(seed_range: (i64, i64), range_mappings: &RangeMappings) -> Vec<(i64, i64)> {
-    let mut split_ranges: Vec<(i64, i64)> = Vec::new();
-    let mut start = seed_range.0;
-    let stop = seed_range.1;
-    let mut incision_points = range_mappings
-        .iter()
-        .map(|range_mapping| range_mapping.from)
-        .filter(|range_from| *range_from > seed_range.0 && *range_from < seed_range.1)
-        .collect::<Vec<i64>>();
-    incision_points.sort();
-    incision_points.reverse();
-    while start < stop {
-        let next_incision = match incision_points.pop() {
-            Some(next_incision) => next_incision,
-            None => stop + 1,
-        };
-        let transform = range_mappings
-            .iter()
-            .filter(|range_mapping| {
-                range_mapping.from <= start && range_mapping.from + range_mapping.range >= start
-            })
-            .map(|range_mapping| range_mapping.to - range_mapping.from)
-            .next();
-        match transform {
-            Some(transform) => {
-                split_ranges.push((start + transform, next_incision + transform - 1));
+fn solve_part_two() -> i64 {
+    let parsed_input = parse_input();
+    parsed_input
+        .seed_with_minimum_location(&parsed_input.seed_ranges())
+        .unwrap()
+        .location
+}
+
+pub fn solve() {
+    println!("Part 1: {}", solve_part_one());
+    println!("Part 2: {}", solve_part_two());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn range_mappings() -> impl Strategy<Value = RangeMappings> {
+        prop::collection::vec(
+            (0i64..200, 0i64..200, 1i64..50).prop_map(|(from, to, range)| RangeMapping {
+                from,
+                to,
+                range,
+            }),
+            0..6,
+        )
+    }
+
+    fn layers() -> impl Strategy<Value = Vec<RangeMappings>> {
+        prop::collection::vec(range_mappings(), 1..8)
+    }
+
+    proptest! {
+        #[test]
+        fn layer_map_matches_point_wise_mapping(
+            range_mappings in range_mappings(),
+            value in -20i64..300,
+        ) {
+            let interval_map = IntervalMap::from_range_mappings(&range_mappings).unwrap();
+            prop_assert_eq!(interval_map.apply(value), map_value(&range_mappings, value));
+        }
+
+        #[test]
+        fn composed_map_matches_point_wise_mapping(layers in layers(), value in -20i64..300) {
+            let composed = layers.iter().fold(IntervalMap::identity(), |composed, layer| {
+                composed.then(&IntervalMap::from_range_mappings(layer).unwrap())
+            });
+            let expected = layers
+                .iter()
+                .fold(value, |mapped_value, layer| map_value(layer, mapped_value));
+            prop_assert_eq!(composed.apply(value), expected);
+        }
+
+        #[test]
+        fn image_of_range_matches_point_wise_mapping(
+            layers in layers(),
+            start in -20i64..300,
+            length in 0i64..60,
+        ) {
+            let composed = layers.iter().fold(IntervalMap::identity(), |composed, layer| {
+                composed.then(&IntervalMap::from_range_mappings(layer).unwrap())
+            });
+            let image = composed.image_of_range(start..start + length);
+            for value in start..start + length {
+                let mapped_value = layers
+                    .iter()
+                    .fold(value, |mapped_value, layer| map_value(layer, mapped_value));
+                prop_assert!(image.iter().any(|range| range.contains(&mapped_value)));
             }
-            None => {
-                split_ranges.push((start, next_incision - 1));
+            let expected_minimum = (start..start + length)
+                .map(|value| layers.iter().fold(value, |mapped_value, layer| map_value(layer, mapped_value)))
+                .min();
+            prop_assert_eq!(image.first().map(|range| range.start), expected_minimum);
+        }
+
+        #[test]
+        fn preimage_contains_every_source(range_mappings in range_mappings(), value in -20i64..300) {
+            let interval_map = IntervalMap::from_range_mappings(&range_mappings).unwrap();
+            let mapped_value = map_value(&range_mappings, value);
+            prop_assert!(interval_map.preimage(mapped_value).contains(&value));
+            prop_assert!(interval_map
+                .preimage_of_range(mapped_value..mapped_value + 1)
+                .iter()
+                .any(|range| range.contains(&value)));
+        }
+
+        #[test]
+        fn inverse_undoes_bijective_maps(
+            shifts in prop::collection::vec((0i64..100, 1i64..20), 1..5),
+            value in -20i64..300,
+        ) {
+            let mut range_mappings = Vec::new();
+            let mut start = 0;
+            for (gap, range) in shifts {
+                start += gap;
+                range_mappings.push((start, range));
+                start += range;
             }
+            let destinations = range_mappings
+                .iter()
+                .scan(start + 1000, |destination, (_, range)| {
+                    let to = *destination;
+                    *destination += range;
+                    Some(to)
+                })
+                .collect::<Vec<i64>>();
+            let swapped = range_mappings
+                .iter()
+                .zip(destinations)
+                .flat_map(|((from, range), to)| {
+                    [
+                        RangeMapping { from: *from, to, range: *range },
+                        RangeMapping { from: to, to: *from, range: *range },
+                    ]
+                })
+                .collect::<RangeMappings>();
+            let interval_map = IntervalMap::from_range_mappings(&swapped).unwrap();
+            let inverse = interval_map.inverse().unwrap();
+            prop_assert_eq!(inverse.apply(interval_map.apply(value)), value);
+            prop_assert_eq!(interval_map.then(&inverse), IntervalMap::identity());
         }
-        start = next_incision;
     }
-    return split_ranges;
-}
 
-fn solve_part_two() -> i64 {
-    let parsed_input = parse_input();
-    let mut seed_ranges = Vec::new();
-    for i in (0..parsed_input.seeds.len() - 1).step_by(2) {
-        seed_ranges.push((
-            parsed_input.seeds[i],
-            parsed_input.seeds[i] + parsed_input.seeds[i + 1],
-        ));
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn example_reverse_queries() {
+        let almanac = parse_almanac(EXAMPLE).unwrap();
+        assert_eq!(almanac.categories.last().unwrap(), "location");
+        assert_eq!(almanac.seeds_producing("soil", 81), Some(vec![79]));
+        assert_eq!(almanac.seeds_producing("location", 46), Some(vec![82]));
+        assert_eq!(almanac.seeds_producing("dragon", 46), None);
+        assert!(almanac
+            .seed_ranges_producing("location", 35..36)
+            .unwrap()
+            .iter()
+            .any(|range| range.contains(&13)));
+        assert_eq!(
+            almanac.seed_with_minimum_location(&almanac.seed_ranges()),
+            Some(SeedLocation {
+                seed: 82,
+                location: 46
+            })
+        );
     }
 
-    return seed_ranges
-        .into_iter()
-        .map(|seed_range| {
-            let mut transformed_ranges = Vec::from([seed_range]);
-            let mut new_ranges = Vec::new();
-            parsed_input
-                .all_range_mappings
-                .iter()
-                .for_each(|range_mappings| {
-                    transformed_ranges
-                        .clone()
-                        .into_iter()
-                        .for_each(|transformed_range| {
-                            let split_ranges = split_seed_range(transformed_range, range_mappings);
-                            new_ranges.extend(split_ranges);
-                        });
-                    transformed_ranges = new_ranges.clone();
-                    new_ranges = Vec::new();
-                });
-            return transformed_ranges
-                .into_iter()
-                .map(|range| return if range.1 < range.0 { range.1 } else { range.0 })
-                .min();
-        })
-        .min()
-        .unwrap()
+    proptest! {
+        #[test]
+        fn seed_with_minimum_location_matches_brute_force(
+            layers in layers(),
+            start in 0i64..200,
+            length in 1i64..60,
+        ) {
+            let almanac = ParsedInput::new(
+                vec![start, length],
+                (0..=layers.len()).map(|index| index.to_string()).collect(),
+                layers.clone(),
+            )
+            .unwrap();
+            let expected = (start..start + length)
+                .map(|seed| SeedLocation {
+                    seed,
+                    location: layers.iter().fold(seed, |mapped_value, layer| map_value(layer, mapped_value)),
+                })
+                .min_by_key(|seed_location| (seed_location.location, seed_location.seed));
+            prop_assert_eq!(almanac.seed_with_minimum_location(&almanac.seed_ranges()), expected);
+            let location = expected.unwrap().location;
+            let category = layers.len().to_string();
+            prop_assert!(almanac.seeds_producing(&category, location).unwrap().contains(&expected.unwrap().seed));
+        }
+    }
+
+    #[test]
+    fn overlapping_images_have_no_inverse() {
+        let interval_map = IntervalMap::from_range_mappings(&[RangeMapping {
+            from: 0,
+            to: 10,
+            range: 5,
+        }])
         .unwrap();
-}
+        assert_eq!(interval_map.inverse(), None);
+    }
 
-pub fn solve() {
-    println!("Part 1: {}", solve_part_one());
-    println!("Part 2: {}", solve_part_two());
+    #[test]
+    fn mappings_at_the_edge_of_i64() {
+        let near_top = RangeMapping {
+            from: 0,
+            to: i64::MAX - 10,
+            range: 10,
+        };
+        let interval_map = IntervalMap::from_range_mappings(&[near_top]).unwrap();
+        assert_eq!(interval_map.apply(9), i64::MAX - 1);
+        let past_top = RangeMapping {
+            to: i64::MAX - 5,
+            ..near_top
+        };
+        assert_eq!(
+            IntervalMap::from_range_mappings(&[past_top]),
+            Err(RangeMappingError::Overflow(past_top))
+        );
+        let swap_extremes = IntervalMap::from_range_mappings(&[
+            RangeMapping {
+                from: i64::MIN,
+                to: i64::MAX - 5,
+                range: 5,
+            },
+            RangeMapping {
+                from: i64::MAX - 5,
+                to: i64::MIN,
+                range: 5,
+            },
+        ])
+        .unwrap();
+        assert_eq!(swap_extremes.apply(i64::MIN), i64::MAX - 5);
+        let round_trip = swap_extremes.then(&swap_extremes);
+        assert_eq!(round_trip, IntervalMap::identity());
+        assert_eq!(swap_extremes.inverse(), Some(swap_extremes.clone()));
+    }
 }
@@ -1,62 +1,97 @@
-fn get_one_integer(line: &str) -> i64 {
+use std::ops::RangeInclusive;
+
+use num_bigint::BigUint;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Race {
+    pub time: BigUint,
+    pub record: BigUint,
+}
+
+impl Race {
+    pub fn distance(&self, held_button_for: &BigUint) -> BigUint {
+        if held_button_for > &self.time {
+            return BigUint::ZERO;
+        }
+        held_button_for * (&self.time - held_button_for)
+    }
+
+    pub fn beats_record(&self, held_button_for: &BigUint) -> bool {
+        self.distance(held_button_for) > self.record
+    }
+
+    pub fn winning_holds(&self) -> Option<RangeInclusive<BigUint>> {
+        let squared_time = &self.time * &self.time;
+        let four_record = &self.record * 4u32;
+        if squared_time <= four_record {
+            return None;
+        }
+        let discriminant_root = (squared_time - four_record).sqrt();
+        let mut first_winning_hold = (&self.time - discriminant_root) / 2u32;
+        while !self.beats_record(&first_winning_hold) {
+            first_winning_hold += 1u32;
+            if &first_winning_hold * 2u32 > self.time {
+                return None;
+            }
+        }
+        let last_winning_hold = &self.time - &first_winning_hold;
+        Some(first_winning_hold..=last_winning_hold)
+    }
+
+    pub fn margin(&self) -> BigUint {
+        match self.winning_holds() {
+            Some(winning_holds) => winning_holds.end() - winning_holds.start() + 1u32,
+            None => BigUint::ZERO,
+        }
+    }
+}
+
+fn get_one_integer(line: &str) -> BigUint {
     line.chars()
-        .filter(|s| s.is_numeric())
+        .filter(|s| s.is_ascii_digit())
         .collect::<String>()
-        .parse::<i64>()
+        .parse::<BigUint>()
         .unwrap()
 }
 
-fn get_all_integers(line: &str) -> Vec<i32> {
-    line.split(" ")
-        .filter_map(|s| match s.parse::<i32>() {
-            Ok(n) => Some(n),
-            Err(_) => None,
-        })
-        .collect::<Vec<i32>>()
+fn get_all_integers(line: &str) -> Vec<BigUint> {
+    line.split_whitespace()
+        .filter_map(|s| s.parse::<BigUint>().ok())
+        .collect::<Vec<BigUint>>()
 }
 
-fn solve_part_one() -> i32 {
-    let file = std::fs::read_to_string("input/day06").unwrap();
-    let lines = file
+pub fn parse_races(input: &str) -> Vec<Race> {
+    let lines = input
         .split("\n")
         .filter(|line| !line.is_empty())
         .collect::<Vec<&str>>();
-    let split_time_line = get_all_integers(lines[0]);
-    let split_distance_line = get_all_integers(lines[1]);
-    (0..split_time_line.len())
-        .map(|i| {
-            let time = split_time_line[i];
-            let distance = split_distance_line[i];
-            (0..time)
-                .map(|held_button_for| {
-                    let race_time = time - held_button_for;
-                    let raced_distance = held_button_for * race_time;
-                    raced_distance
-                })
-                .filter(|raced_distance| *raced_distance > distance)
-                .collect::<Vec<i32>>()
-                .len() as i32
-        })
-        .product()
+    get_all_integers(lines[0])
+        .into_iter()
+        .zip(get_all_integers(lines[1]))
+        .map(|(time, record)| Race { time, record })
+        .collect()
 }
 
-fn solve_part_two() -> i32 {
-    let file = std::fs::read_to_string("input/day06").unwrap();
-    let lines = file
+pub fn parse_single_race(input: &str) -> Race {
+    let lines = input
         .split("\n")
         .filter(|line| !line.is_empty())
         .collect::<Vec<&str>>();
-    let time = get_one_integer(lines[0]);
-    let distance = get_one_integer(lines[1]);
-    return (0..time)
-        .map(|held_button_for| {
-            let race_time = time - held_button_for;
-            let raced_distance = held_button_for * race_time;
-            raced_distance
-        })
-        .filter(|raced_distance| *raced_distance > distance)
-        .collect::<Vec<i64>>()
-        .len() as i32;
+    Race {
+        time: get_one_integer(lines[0]),
+        record: get_one_integer(lines[1]),
+    }
+}
+
+fn solve_part_one() -> BigUint {
+    parse_races(&std::fs::read_to_string("input/day06").unwrap())
+        .iter()
+        .map(|race| race.margin())
+        .product()
+}
+
+fn solve_part_two() -> BigUint {
+    parse_single_race(&std::fs::read_to_string("input/day06").unwrap()).margin()
 }
 
 pub fn solve() {
@@ -1,152 +1,249 @@
-use std::collections::HashMap;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandCategory {
+    pub name: String,
+    pub groups: Vec<usize>,
+}
 
-#[derive(Debug)]
-struct Hand {
-    cards: String,
-    score: i32,
-    jokers_are_wild: bool,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardRules {
+    pub card_order: Vec<char>,
+    pub wildcards: Vec<char>,
+    pub hand_size: usize,
+    pub categories: Vec<HandCategory>,
 }
 
-fn get_card_value(card: &char, jokers_are_wild: bool) -> i32 {
-    return match card {
-        'A' => 14,
-        'K' => 13,
-        'Q' => 12,
-        'J' => {
-            if jokers_are_wild {
-                1
-            } else {
-                11
-            }
-        }
-        'T' => 10,
-        _ => card.to_digit(10).unwrap() as i32,
-    };
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandKey(u128);
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hand {
+    pub key: HandKey,
+    pub category: usize,
+    pub cards: String,
+    pub bid: u64,
 }
 
-fn get_card_occurences(cards: &str) -> HashMap<char, i32> {
-    cards
-        .chars()
-        .into_iter()
-        .fold(HashMap::new(), |mut acc, card| {
-            *acc.entry(card).or_insert(0) += 1;
-            return acc;
-        })
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryTies {
+    pub name: String,
+    pub hands: usize,
+    pub tied_hands: usize,
+    pub largest_tie: usize,
 }
 
-fn hand_type_score(hand: &Hand) -> i32 {
-    let mut card_occurences = get_card_occurences(&hand.cards);
-    if hand.jokers_are_wild
-        && card_occurences
-            .keys()
-            .collect::<Vec<&char>>()
-            .contains(&&'J')
-    {
-        if card_occurences[&'J'] == 5 {
-            return 7;
-        } else {
-            let max_key = card_occurences
-                .iter()
-                .filter(|&x| *x.0 != 'J')
-                .max_by_key(|x| x.1)
-                .unwrap();
-            card_occurences = get_card_occurences(&hand.cards.replace('J', &max_key.0.to_string()));
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandError {
+    WrongHandSize { cards: String, expected: usize },
+    UnknownCard { cards: String, card: char },
+    NoMatchingCategory { cards: String },
+    KeyTooWide { bits: u32 },
+    MalformedLine { line: String },
+}
+
+fn category(name: &str, groups: &[usize]) -> HandCategory {
+    HandCategory {
+        name: name.to_string(),
+        groups: groups.to_vec(),
+    }
+}
+
+fn poker_categories() -> Vec<HandCategory> {
+    vec![
+        category("high card", &[1]),
+        category("one pair", &[2]),
+        category("two pair", &[2, 2]),
+        category("three of a kind", &[3]),
+        category("full house", &[3, 2]),
+        category("four of a kind", &[4]),
+        category("five of a kind", &[5]),
+    ]
+}
+
+impl CardRules {
+    pub fn camel_cards() -> Self {
+        CardRules {
+            card_order: "23456789TJQKA".chars().collect(),
+            wildcards: Vec::new(),
+            hand_size: 5,
+            categories: poker_categories(),
         }
     }
-    match card_occurences.values().collect::<Vec<&i32>>() {
-        v if v.contains(&&5) => 7,
-        v if v.contains(&&4) => 6,
-        v if v.contains(&&3) && v.contains(&&2) => 5,
-        v if v.contains(&&3) => 4,
-        v if v
-            .clone()
-            .into_iter()
-            .filter(|&&x| x == 2)
-            .collect::<Vec<&i32>>()
-            .len()
-            == 2 =>
-        {
-            3
+
+    pub fn camel_cards_with_jokers() -> Self {
+        CardRules {
+            card_order: "J23456789TQKA".chars().collect(),
+            wildcards: vec!['J'],
+            hand_size: 5,
+            categories: poker_categories(),
         }
-        v if v.contains(&&2) => 2,
-        v if v.len() == 5 => 1,
-        _ => panic!("{}", format!("invalid hand: {:?}", hand.cards)),
     }
-}
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let self_cards = self.cards.chars().collect::<Vec<char>>();
-        let other_cards = other.cards.chars().collect::<Vec<char>>();
-
-        if hand_type_score(&self) == hand_type_score(&other) {
-            for i in 0..self.cards.len() {
-                if self_cards[i] != other_cards[i] {
-                    return get_card_value(&self_cards[i], self.jokers_are_wild)
-                        .cmp(&get_card_value(&other_cards[i], other.jokers_are_wild));
-                }
+    pub fn deuces_wild() -> Self {
+        CardRules {
+            card_order: "23456789TJQKA".chars().collect(),
+            wildcards: vec!['2'],
+            hand_size: 5,
+            categories: poker_categories(),
+        }
+    }
+
+    pub fn card_value(&self, card: char) -> Option<usize> {
+        self.card_order
+            .iter()
+            .position(|candidate| *candidate == card)
+    }
+
+    fn card_values(&self, cards: &str) -> Result<Vec<usize>, HandError> {
+        let card_values = cards
+            .chars()
+            .map(|card| {
+                self.card_value(card).ok_or(HandError::UnknownCard {
+                    cards: cards.to_string(),
+                    card,
+                })
+            })
+            .collect::<Result<Vec<usize>, HandError>>()?;
+        if card_values.len() != self.hand_size {
+            return Err(HandError::WrongHandSize {
+                cards: cards.to_string(),
+                expected: self.hand_size,
+            });
+        }
+        Ok(card_values)
+    }
+
+    fn category_of_values(&self, card_values: &[usize]) -> Option<usize> {
+        let mut wildcards = 0;
+        let mut card_occurences = vec![0; self.card_order.len()];
+        for card_value in card_values {
+            if self.wildcards.contains(&self.card_order[*card_value]) {
+                wildcards += 1;
+            } else {
+                card_occurences[*card_value] += 1;
             }
         }
-        return hand_type_score(&self).cmp(&hand_type_score(&other));
+        card_occurences.sort_by(|a, b| b.cmp(a));
+        self.categories.iter().rposition(|category| {
+            let mut required_groups = category.groups.clone();
+            required_groups.sort_by(|a, b| b.cmp(a));
+            let missing_cards = required_groups
+                .iter()
+                .enumerate()
+                .map(|(index, required)| {
+                    required.saturating_sub(card_occurences.get(index).copied().unwrap_or(0))
+                })
+                .sum::<usize>();
+            missing_cards <= wildcards
+        })
+    }
+
+    pub fn category_index(&self, cards: &str) -> Result<usize, HandError> {
+        self.category_of_values(&self.card_values(cards)?)
+            .ok_or(HandError::NoMatchingCategory {
+                cards: cards.to_string(),
+            })
+    }
+
+    pub fn category_name(&self, cards: &str) -> Result<&str, HandError> {
+        Ok(&self.categories[self.category_index(cards)?].name)
+    }
+
+    fn bits_for(values: usize) -> u32 {
+        usize::BITS - values.saturating_sub(1).leading_zeros()
+    }
+
+    pub fn hand(&self, cards: &str, bid: u64) -> Result<Hand, HandError> {
+        let card_values = self.card_values(cards)?;
+        let category =
+            self.category_of_values(&card_values)
+                .ok_or(HandError::NoMatchingCategory {
+                    cards: cards.to_string(),
+                })?;
+        let card_bits = Self::bits_for(self.card_order.len());
+        let bits = Self::bits_for(self.categories.len()) + card_bits * self.hand_size as u32;
+        if bits > u128::BITS {
+            return Err(HandError::KeyTooWide { bits });
+        }
+        let key = card_values
+            .iter()
+            .fold(category as u128, |key, card_value| {
+                (key << card_bits) | *card_value as u128
+            });
+        Ok(Hand {
+            key: HandKey(key),
+            category,
+            cards: cards.to_string(),
+            bid,
+        })
     }
-}
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+    pub fn parse_hands(&self, input: &str) -> Result<Vec<Hand>, HandError> {
+        input
+            .split("\n")
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let malformed = || HandError::MalformedLine {
+                    line: line.to_string(),
+                };
+                let (cards, bid) = line.split_once(" ").ok_or_else(malformed)?;
+                self.hand(cards, bid.trim().parse::<u64>().map_err(|_| malformed())?)
+            })
+            .collect()
     }
-}
 
-impl PartialEq for Hand {
-    fn eq(&self, other: &Self) -> bool {
-        return hand_type_score(&self) == hand_type_score(&other);
+    pub fn tie_statistics(&self, hands: &[Hand]) -> Vec<CategoryTies> {
+        let mut keys = hands
+            .iter()
+            .map(|hand| (hand.category, hand.key))
+            .collect::<Vec<(usize, HandKey)>>();
+        keys.sort_unstable();
+        let mut category_ties = self
+            .categories
+            .iter()
+            .map(|category| CategoryTies {
+                name: category.name.clone(),
+                hands: 0,
+                tied_hands: 0,
+                largest_tie: 0,
+            })
+            .collect::<Vec<CategoryTies>>();
+        for tie in keys.chunk_by(|a, b| a == b) {
+            let ties = &mut category_ties[tie[0].0];
+            ties.hands += tie.len();
+            if tie.len() > 1 {
+                ties.tied_hands += tie.len();
+            }
+            ties.largest_tie = ties.largest_tie.max(tie.len());
+        }
+        category_ties
     }
 }
 
-impl Eq for Hand {}
-
-fn parse_hands(jokers_are_wild: bool) -> Vec<Hand> {
-    let file = std::fs::read_to_string("input/day07").unwrap();
-    let mut hands = file
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let [cards_str, score_str] = line.split(" ").collect::<Vec<&str>>()[..] else {
-                panic!();
-            };
-            return Hand {
-                cards: cards_str.to_string(),
-                score: score_str.parse::<i32>().unwrap(),
-                jokers_are_wild,
-            };
-        })
-        .collect::<Vec<Hand>>();
-    hands.sort_by(|a, b| a.cmp(&b));
-    hands
+pub fn rank_hands(hands: &mut [Hand]) {
+    hands.sort_unstable();
 }
 
-fn solve_part_one() -> i32 {
-    let hands = parse_hands(false);
-    let hand_scores = hands
+pub fn total_winnings(mut hands: Vec<Hand>) -> u64 {
+    rank_hands(&mut hands);
+    hands
         .iter()
         .enumerate()
-        .map(|(index, hand)| {
-            return (index as i32 + 1) * hand.score;
-        })
-        .sum();
-    return hand_scores;
+        .map(|(index, hand)| (index as u64 + 1) * hand.bid)
+        .sum()
 }
 
-pub fn solve_part_two() -> i32 {
-    let hands = parse_hands(true);
-    let hand_scores = hands
-        .iter()
-        .enumerate()
-        .map(|(index, hand)| {
-            return (index as i32 + 1) * hand.score;
-        })
-        .sum();
-    return hand_scores;
+fn read_hands(rules: &CardRules) -> Vec<Hand> {
+    rules
+        .parse_hands(&std::fs::read_to_string("input/day07").unwrap())
+        .unwrap()
+}
+
+fn solve_part_one() -> u64 {
+    total_winnings(read_hands(&CardRules::camel_cards()))
+}
+
+fn solve_part_two() -> u64 {
+    total_winnings(read_hands(&CardRules::camel_cards_with_jokers()))
 }
 
 pub fn solve() {
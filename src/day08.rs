@@ -1,110 +1,369 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::ops::ControlFlow;
 
-fn greatest_common_divisor(a: i64, b: i64) -> i64 {
-    let mut num1 = a;
-    let mut num2 = b;
-    if num1 == num2 {
-        return num1;
-    }
-    if num2 > num1 {
-        let tmp = num1;
-        num1 = num2;
-        num2 = tmp;
-    }
-    while num2 > 0 {
-        let tmp = num1;
-        num1 = num2;
-        num2 = tmp % num2;
-    }
-    return num1;
+use crate::number_theory::{crt_pair, NumberTheoryError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
 }
 
-pub fn lowest_common_multiple(numbers: Vec<i64>) -> i64 {
-    let mut lowest_common = numbers[0];
-    numbers.into_iter().for_each(|number| {
-        lowest_common = lowest_common * (number / greatest_common_divisor(lowest_common, number));
-    });
-    return lowest_common;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkError {
+    MalformedLine(String),
+    InvalidInstruction(char),
+    NoInstructions,
+    UnknownNode(String),
+    Unreachable { start: String, goal: String },
 }
 
-fn solve_part_one() -> i32 {
-    let file = std::fs::read_to_string("input/day08").unwrap();
-    let split_file = file.split("\n\n").collect::<Vec<&str>>();
-    let mut instructions = split_file[0].chars().collect::<Vec<char>>();
-    let mut network_map = HashMap::new();
-    split_file[1]
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .for_each(|line| {
-            let [key, value_string] = line.split(" = ").collect::<Vec<&str>>()[..] else {
-                panic!("Could not parse line: {}", line);
-            };
-            let value = value_string.split(", ").collect::<Vec<&str>>();
-            let left_value = &value[0][1..];
-            let right_value = &value[1][..value[1].len() - 1];
-            network_map.insert(key, (left_value, right_value));
-        });
-    let mut current_location = "AAA";
-    let mut steps = 0;
-    while current_location != "ZZZ" {
-        current_location = match instructions[0] {
-            'R' => network_map[&current_location].1,
-            'L' => network_map[&current_location].0,
-            _ => panic!("Invalid instruction: {}", instructions[0]),
-        };
-        steps += 1;
-        instructions.rotate_left(1);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    pub instructions: Vec<Direction>,
+    nodes: Vec<String>,
+    node_indices: HashMap<String, usize>,
+    edges: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhostCycle {
+    pub tail: u64,
+    pub cycle: u64,
+    pub tail_hits: Vec<u64>,
+    pub cycle_hits: Vec<u64>,
+}
+
+impl GhostCycle {
+    pub fn hits_at(&self, step: u64) -> bool {
+        if step < self.tail {
+            return self.tail_hits.contains(&step);
+        }
+        let offset = self.tail + (step - self.tail) % self.cycle;
+        self.cycle_hits.binary_search(&offset).is_ok()
     }
-    return steps;
 }
 
-fn solve_part_two() -> i64 {
-    let file = std::fs::read_to_string("input/day08").unwrap();
-    let split_file = file.split("\n\n").collect::<Vec<&str>>();
-    let mut instructions = split_file[0].chars().collect::<Vec<char>>();
-    let mut network_map = HashMap::new();
-    split_file[1]
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .for_each(|line| {
-            let [key, value_string] = line.split(" = ").collect::<Vec<&str>>()[..] else {
-                panic!("Could not parse line: {}", line);
-            };
-            let value = value_string.split(", ").collect::<Vec<&str>>();
-            let left_value = &value[0][1..];
-            let right_value = &value[1][..value[1].len() - 1];
-            network_map.insert(key, (left_value, right_value));
-        });
-    let mut current_locations = network_map
-        .keys()
-        .filter(|&&key| key.chars().collect::<Vec<char>>()[2] == 'A')
-        .map(|&key| key)
-        .collect::<Vec<&str>>();
-    let mut steps_to_z = Vec::from([0, 0, 0, 0, 0, 0]);
-    let mut steps = 0;
-    while !steps_to_z.iter().all(|x| *x > 0) {
-        current_locations = current_locations
+impl Network {
+    pub fn parse(file: &str) -> Result<Self, NetworkError> {
+        let (instruction_line, node_lines) = file
+            .split_once("\n\n")
+            .ok_or(NetworkError::MalformedLine(file.to_string()))?;
+        let instructions = instruction_line
+            .trim()
+            .chars()
+            .map(|instruction| match instruction {
+                'L' => Ok(Direction::Left),
+                'R' => Ok(Direction::Right),
+                _ => Err(NetworkError::InvalidInstruction(instruction)),
+            })
+            .collect::<Result<Vec<Direction>, NetworkError>>()?;
+        if instructions.is_empty() {
+            return Err(NetworkError::NoInstructions);
+        }
+        let mut named_edges = Vec::new();
+        for line in node_lines.split("\n").filter(|line| !line.is_empty()) {
+            let malformed = || NetworkError::MalformedLine(line.to_string());
+            let (key, value_string) = line.split_once(" = ").ok_or_else(malformed)?;
+            let (left_value, right_value) = value_string
+                .strip_prefix('(')
+                .and_then(|value| value.strip_suffix(')'))
+                .and_then(|value| value.split_once(", "))
+                .ok_or_else(malformed)?;
+            named_edges.push((key, left_value, right_value));
+        }
+        let nodes = named_edges
+            .iter()
+            .map(|(key, _, _)| key.to_string())
+            .collect::<Vec<String>>();
+        let node_indices = nodes
             .iter()
             .enumerate()
-            .map(|(index, location)| {
-                if steps_to_z[index] > 0 {
-                    return *location;
-                };
-                let next_location = match instructions[0] {
-                    'R' => network_map[location].1,
-                    'L' => network_map[location].0,
-                    _ => panic!("Invalid instruction: {}", instructions[0]),
-                };
-                if next_location.chars().collect::<Vec<char>>()[2] == 'Z' {
-                    steps_to_z[index] = steps + 1;
+            .map(|(index, node)| (node.clone(), index))
+            .collect::<HashMap<String, usize>>();
+        let index_of = |node: &str| {
+            node_indices
+                .get(node)
+                .copied()
+                .ok_or(NetworkError::UnknownNode(node.to_string()))
+        };
+        let edges = named_edges
+            .iter()
+            .map(|(_, left_value, right_value)| Ok((index_of(left_value)?, index_of(right_value)?)))
+            .collect::<Result<Vec<(usize, usize)>, NetworkError>>()?;
+        Ok(Network {
+            instructions,
+            nodes,
+            node_indices,
+            edges,
+        })
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &str> {
+        self.nodes.iter().map(|node| node.as_str())
+    }
+
+    pub fn neighbours(&self, node: &str) -> Option<(&str, &str)> {
+        let (left, right) = self.edges[*self.node_indices.get(node)?];
+        Some((&self.nodes[left], &self.nodes[right]))
+    }
+
+    fn index_of(&self, node: &str) -> Result<usize, NetworkError> {
+        self.node_indices
+            .get(node)
+            .copied()
+            .ok_or(NetworkError::UnknownNode(node.to_string()))
+    }
+
+    fn step(&self, location: usize, step: u64) -> usize {
+        match self.instructions[step as usize % self.instructions.len()] {
+            Direction::Left => self.edges[location].0,
+            Direction::Right => self.edges[location].1,
+        }
+    }
+
+    fn walk_until_repeat(
+        &self,
+        start: usize,
+        mut visit: impl FnMut(usize, u64) -> ControlFlow<()>,
+    ) -> Option<(u64, u64)> {
+        let mut first_visits: HashMap<(usize, usize), u64> = HashMap::new();
+        let mut location = start;
+        let mut step = 0;
+        loop {
+            let instruction_index = step as usize % self.instructions.len();
+            if let Some(first_visit) = first_visits.insert((location, instruction_index), step) {
+                return Some((first_visit, step - first_visit));
+            }
+            visit(location, step).continue_value()?;
+            location = self.step(location, step);
+            step += 1;
+        }
+    }
+
+    fn connected_from(&self, start: usize) -> Vec<bool> {
+        let mut connected = vec![false; self.nodes.len()];
+        let mut pending = vec![start];
+        connected[start] = true;
+        while let Some(location) = pending.pop() {
+            let (left, right) = self.edges[location];
+            for next in [left, right] {
+                if !connected[next] {
+                    connected[next] = true;
+                    pending.push(next);
                 }
-                return next_location;
+            }
+        }
+        connected
+    }
+
+    pub fn reachable_from(&self, start: &str) -> Result<BTreeSet<&str>, NetworkError> {
+        let mut reachable = BTreeSet::new();
+        self.walk_until_repeat(self.index_of(start)?, |location, _| {
+            reachable.insert(self.nodes[location].as_str());
+            ControlFlow::Continue(())
+        });
+        Ok(reachable)
+    }
+
+    pub fn ghost_starts(&self) -> Vec<&str> {
+        self.nodes().filter(|node| node.ends_with('A')).collect()
+    }
+
+    pub fn reachable_from_ghost_starts(&self) -> BTreeMap<&str, BTreeSet<&str>> {
+        self.ghost_starts()
+            .into_iter()
+            .map(|start| (start, self.reachable_from(start).unwrap()))
+            .collect()
+    }
+
+    pub fn steps_between(&self, start: &str, goal: &str) -> Result<u64, NetworkError> {
+        let goal_index = self.index_of(goal)?;
+        let start_index = self.index_of(start)?;
+        let unreachable = NetworkError::Unreachable {
+            start: start.to_string(),
+            goal: goal.to_string(),
+        };
+        if !self.connected_from(start_index)[goal_index] {
+            return Err(unreachable);
+        }
+        let mut first_arrival = None;
+        self.walk_until_repeat(start_index, |location, step| {
+            if location == goal_index {
+                first_arrival = Some(step);
+                return ControlFlow::Break(());
+            }
+            ControlFlow::Continue(())
+        });
+        first_arrival.ok_or(unreachable)
+    }
+
+    pub fn analyse_ghost(
+        &self,
+        start: &str,
+        is_goal: impl Fn(&str) -> bool,
+    ) -> Result<GhostCycle, NetworkError> {
+        let mut hits = Vec::new();
+        let (tail, cycle) = self
+            .walk_until_repeat(self.index_of(start)?, |location, step| {
+                if is_goal(&self.nodes[location]) {
+                    hits.push(step);
+                }
+                ControlFlow::Continue(())
             })
-            .collect::<Vec<&str>>();
-        steps += 1;
-        instructions.rotate_left(1);
+            .unwrap();
+        let cycle_start = hits.partition_point(|hit| *hit < tail);
+        Ok(GhostCycle {
+            tail,
+            cycle,
+            cycle_hits: hits.split_off(cycle_start),
+            tail_hits: hits,
+        })
+    }
+
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&str>> {
+        let node_count = self.nodes.len();
+        let mut reversed_edges = vec![Vec::new(); node_count];
+        for (from, (left, right)) in self.edges.iter().enumerate() {
+            reversed_edges[*left].push(from);
+            reversed_edges[*right].push(from);
+        }
+        let mut visited = vec![false; node_count];
+        let mut finish_order = Vec::with_capacity(node_count);
+        for root in 0..node_count {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut stack = vec![(root, 0)];
+            while let Some((node, next_edge)) = stack.pop() {
+                let (left, right) = self.edges[node];
+                match [left, right].get(next_edge) {
+                    Some(&neighbour) => {
+                        stack.push((node, next_edge + 1));
+                        if !visited[neighbour] {
+                            visited[neighbour] = true;
+                            stack.push((neighbour, 0));
+                        }
+                    }
+                    None => finish_order.push(node),
+                }
+            }
+        }
+        let mut component_of = vec![usize::MAX; node_count];
+        let mut components: Vec<Vec<&str>> = Vec::new();
+        for root in finish_order.into_iter().rev() {
+            if component_of[root] != usize::MAX {
+                continue;
+            }
+            let mut component = Vec::new();
+            component_of[root] = components.len();
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                component.push(self.nodes[node].as_str());
+                for previous in &reversed_edges[node] {
+                    if component_of[*previous] == usize::MAX {
+                        component_of[*previous] = components.len();
+                        stack.push(*previous);
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+        components
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph network {\n");
+        for (node, (left, right)) in self.nodes.iter().zip(&self.edges) {
+            let shape = match node.chars().last() {
+                Some('A') => "box",
+                Some('Z') => "doublecircle",
+                _ => "ellipse",
+            };
+            writeln!(dot, "    \"{}\" [shape={}];", node, shape).unwrap();
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"L\"];",
+                node, self.nodes[*left]
+            )
+            .unwrap();
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"R\"];",
+                node, self.nodes[*right]
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
     }
-    return lowest_common_multiple(steps_to_z);
+}
+
+fn read_network() -> Network {
+    Network::parse(&std::fs::read_to_string("input/day08").unwrap()).unwrap()
+}
+
+fn solve_part_one() -> u64 {
+    read_network().steps_between("AAA", "ZZZ").unwrap()
+}
+
+pub fn first_simultaneous_arrival(ghosts: &[GhostCycle]) -> Result<Option<u64>, NumberTheoryError> {
+    let Some(longest_tail) = ghosts.iter().map(|ghost| ghost.tail).max() else {
+        return Ok(None);
+    };
+    if let Some(step) =
+        (0..longest_tail).find(|step| ghosts.iter().all(|ghost| ghost.hits_at(*step)))
+    {
+        return Ok(Some(step));
+    }
+    let mut congruences = vec![(0i128, 1i128)];
+    for ghost in ghosts {
+        let mut combined = Vec::new();
+        for hit in &ghost.cycle_hits {
+            for congruence in &congruences {
+                match crt_pair(*congruence, (*hit as i128, ghost.cycle as i128)) {
+                    Ok(solution) => combined.push(solution),
+                    Err(NumberTheoryError::NoSolution) => continue,
+                    Err(error) => return Err(error),
+                }
+            }
+        }
+        combined.sort();
+        combined.dedup();
+        congruences = combined;
+    }
+    let tail = longest_tail as i128;
+    congruences
+        .into_iter()
+        .map(|(remainder, modulus)| {
+            let periods_to_tail = ((tail - remainder).max(0) + modulus - 1) / modulus;
+            modulus
+                .checked_mul(periods_to_tail)
+                .and_then(|offset| offset.checked_add(remainder))
+                .and_then(|step| u64::try_from(step).ok())
+                .ok_or(NumberTheoryError::Overflow)
+        })
+        .try_fold(None, |earliest: Option<u64>, step| {
+            let step = step?;
+            Ok(Some(earliest.map_or(step, |earliest| earliest.min(step))))
+        })
+}
+
+fn solve_part_two() -> u64 {
+    let network = read_network();
+    let ghosts = network
+        .ghost_starts()
+        .into_iter()
+        .map(|start| {
+            network
+                .analyse_ghost(start, |location| location.ends_with('Z'))
+                .unwrap()
+        })
+        .collect::<Vec<GhostCycle>>();
+    first_simultaneous_arrival(&ghosts).unwrap().unwrap()
 }
 
 pub fn solve() {
@@ -1,56 +1,112 @@
-fn parse_input() -> Vec<Vec<i32>> {
+use num_bigint::BigInt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtrapolationWarning {
+    EmptySequence,
+    NeverReachedZero { rows: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extrapolator {
+    leading_differences: Vec<BigInt>,
+    length: usize,
+    pub degree: Option<usize>,
+    pub warning: Option<ExtrapolationWarning>,
+}
+
+impl Extrapolator {
+    pub fn new(sequence: &[BigInt]) -> Self {
+        if sequence.is_empty() {
+            return Extrapolator {
+                leading_differences: Vec::new(),
+                length: 0,
+                degree: None,
+                warning: Some(ExtrapolationWarning::EmptySequence),
+            };
+        }
+        let mut leading_differences = Vec::new();
+        let mut next_line = sequence.to_vec();
+        while !next_line.is_empty() && !next_line.iter().all(|d| *d == BigInt::ZERO) {
+            leading_differences.push(next_line[0].clone());
+            next_line = next_line
+                .windows(2)
+                .map(|w| &w[1] - &w[0])
+                .collect::<Vec<BigInt>>();
+        }
+        let warning = if next_line.is_empty() {
+            Some(ExtrapolationWarning::NeverReachedZero {
+                rows: leading_differences.len(),
+            })
+        } else {
+            None
+        };
+        Extrapolator {
+            degree: leading_differences.len().checked_sub(1),
+            leading_differences,
+            length: sequence.len(),
+            warning,
+        }
+    }
+
+    pub fn value_at(&self, index: i64) -> BigInt {
+        let index = BigInt::from(index);
+        let mut binomial = BigInt::from(1);
+        let mut value = BigInt::ZERO;
+        for (k, difference) in self.leading_differences.iter().enumerate() {
+            value += difference * &binomial;
+            binomial = binomial * (&index - k) / (k + 1);
+        }
+        value
+    }
+
+    pub fn next(&self) -> BigInt {
+        self.value_at(self.length as i64)
+    }
+
+    pub fn previous(&self) -> BigInt {
+        self.value_at(-1)
+    }
+}
+
+fn parse_input() -> Vec<Vec<BigInt>> {
     let file = std::fs::read_to_string("input/day09").unwrap();
     file.split("\n")
         .filter(|s| !s.is_empty())
         .map(|s| {
             s.split(" ")
                 .filter(|s| !s.is_empty())
-                .map(|num| num.parse::<i32>().unwrap())
-                .collect::<Vec<i32>>()
+                .map(|num| num.parse::<BigInt>().unwrap())
+                .collect::<Vec<BigInt>>()
         })
         .collect()
 }
 
-fn solve_part_one() -> i32 {
-    return parse_input()
-        .into_iter()
-        .map(|line| {
-            let mut next_line = line.clone();
-            let mut prediction = next_line[next_line.len() - 1];
-            while !next_line.clone().into_iter().all(|d| d == 0) {
-                next_line = next_line
-                    .clone()
-                    .windows(2)
-                    .map(|w| w[1] - w[0])
-                    .collect::<Vec<i32>>();
-                prediction = prediction + next_line[next_line.len() - 1];
-            }
-            prediction
-        })
-        .sum();
-}
-
-fn solve_part_two() -> i32 {
-    return parse_input()
-        .into_iter()
-        .map(|line| {
-            let mut next_line = line.clone();
-            let mut history = Vec::from([next_line[0]]);
-            while !next_line.clone().into_iter().all(|d| d == 0) {
-                next_line = next_line
-                    .clone()
-                    .windows(2)
-                    .map(|w| w[1] - w[0])
-                    .collect::<Vec<i32>>();
-                history.push(next_line[0]);
+fn extrapolators() -> Vec<Extrapolator> {
+    parse_input()
+        .iter()
+        .enumerate()
+        .map(|(line_number, line)| {
+            let extrapolator = Extrapolator::new(line);
+            if let Some(warning) = &extrapolator.warning {
+                eprintln!("line {}: {:?}", line_number + 1, warning);
             }
-            let mut prediction = 0;
-            (0..(history.len() - 1))
-                .rev()
-                .for_each(|i| prediction = history[i] - prediction);
-            prediction
+            extrapolator
         })
-        .sum();
+        .collect()
+}
+
+fn solve_part_one() -> BigInt {
+    extrapolators()
+        .iter()
+        .map(|extrapolator| extrapolator.next())
+        .sum()
+}
+
+fn solve_part_two() -> BigInt {
+    extrapolators()
+        .iter()
+        .map(|extrapolator| extrapolator.previous())
+        .sum()
 }
 
 pub fn solve() {
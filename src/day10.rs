@@ -1,138 +1,217 @@
-type Point = (usize, usize);
-
-fn parse_map() -> Vec<Vec<char>> {
-    let file = std::fs::read_to_string("input/day10").unwrap();
-    file.split("\n")
-        .filter(|s| !s.is_empty())
-        .map(|s| s.chars().collect())
-        .collect()
+pub type Point = (usize, usize);
+
+const NORTH: u8 = 1;
+const EAST: u8 = 2;
+const SOUTH: u8 = 4;
+const WEST: u8 = 8;
+const DIRECTIONS: [u8; 4] = [NORTH, EAST, SOUTH, WEST];
+const PIPES: [char; 6] = ['|', '-', 'L', 'J', '7', 'F'];
+
+fn connections(pipe: char) -> u8 {
+    match pipe {
+        '|' => NORTH | SOUTH,
+        '-' => EAST | WEST,
+        'L' => NORTH | EAST,
+        'J' => NORTH | WEST,
+        '7' => SOUTH | WEST,
+        'F' => SOUTH | EAST,
+        _ => 0,
+    }
 }
 
-fn find_starting_point(map: &Vec<Vec<char>>) -> Point {
-    map.into_iter()
-        .enumerate()
-        .find_map(|(line_num, s)| match s.iter().position(|c| *c == 'S') {
-            Some(pos) => {
-                return Some((line_num, pos));
-            }
-            None => return None,
-        })
-        .unwrap()
+fn opposite(direction: u8) -> u8 {
+    match direction {
+        NORTH => SOUTH,
+        EAST => WEST,
+        SOUTH => NORTH,
+        _ => EAST,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipeMap {
+    pub width: usize,
+    pub height: usize,
+    tiles: Vec<char>,
 }
 
-fn find_available_points(map: &Vec<Vec<char>>, at: Point) -> Vec<Point> {
-    let x = at.1 as i32;
-    let y = at.0 as i32;
-    let available_points: Vec<(i32, i32)> = match map[at.0][at.1] {
-        'S' => [].to_vec(),
-        '|' => [(y + 1, x), (y - 1, x)].to_vec(),
-        '-' => [(y, x + 1), (y, x - 1)].to_vec(),
-        'J' => [(y - 1, x), (y, x - 1)].to_vec(),
-        '7' => [(y + 1, x), (y, x - 1)].to_vec(),
-        'F' => [(y + 1, x), (y, x + 1)].to_vec(),
-        'L' => [(y - 1, x), (y, x + 1)].to_vec(),
-        '.' => [].to_vec(),
-        _ => panic!("Invalid character {}", map[at.0][at.1]),
-    };
-    available_points
-        .into_iter()
-        .filter(|p| p.0 >= 0 && p.1 >= 0 && p.0 < map.len() as i32 && p.1 < map[0].len() as i32)
-        .map(|p| (p.0 as usize, p.1 as usize))
-        .collect()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipeLoop {
+    pub tiles: Vec<Point>,
 }
 
-fn find_start_pipe_type(pipe_map: &Vec<Vec<char>>, start: Point) -> char {
-    let xs = start.0 as i32;
-    let ys = start.1 as i32;
-    let available_points = [
-        (xs as i32 + 1, ys as i32),
-        (xs as i32 - 1, ys as i32),
-        (xs as i32, ys as i32 + 1),
-        (xs as i32, ys as i32 - 1),
-    ]
-    .into_iter()
-    .filter(|p| {
-        p.0 >= 0
-            && p.1 >= 0
-            && p.0 < pipe_map.len() as i32
-            && p.1 < pipe_map[0].len() as i32
-            && find_available_points(pipe_map, (p.0 as usize, p.1 as usize)).contains(&start)
-    })
-    .collect::<Vec<(i32, i32)>>();
-
-    match available_points.as_slice() {
-        [first, second] if first == &(xs + 1, ys) && second == &(xs - 1, ys) => '|',
-        [first, second] if first == &(xs, ys + 1) && second == &(xs, ys - 1) => '-',
-        [first, second] if first == &(xs - 1, ys) && second == &(xs, ys - 1) => 'J',
-        [first, second] if first == &(xs + 1, ys) && second == &(xs, ys + 1) => 'F',
-        [first, second] if first == &(xs - 1, ys) && second == &(xs, ys + 1) => 'L',
-        [first, second] if first == &(xs + 1, ys) && second == &(xs, ys - 1) => '7',
-        _ => panic!("Invalid character {}", pipe_map[start.0][start.1]),
+impl PipeLoop {
+    pub fn length(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn farthest_distance(&self) -> usize {
+        self.tiles.len() / 2
+    }
+
+    pub fn enclosed_tiles(&self) -> usize {
+        let twice_area = self
+            .tiles
+            .iter()
+            .zip(self.tiles.iter().cycle().skip(1))
+            .map(|(a, b)| a.1 as i64 * b.0 as i64 - b.1 as i64 * a.0 as i64)
+            .sum::<i64>()
+            .unsigned_abs() as usize;
+        (twice_area + 2 - self.tiles.len()) / 2
     }
 }
 
-fn find_loop(pipe_map: &Vec<Vec<char>>, start: Point) -> Vec<Point> {
-    let mut paths: Vec<Vec<Point>> = Vec::from([[start].to_vec()]);
-    loop {
-        let this_path = paths[0].clone();
-        let at = this_path[this_path.len() - 1];
-        let last = if paths.len() == 1 {
-            at
-        } else {
-            this_path[this_path.len() - 2]
-        };
-        let available_points = find_available_points(pipe_map, at)
+impl PipeMap {
+    pub fn parse(input: &str) -> Self {
+        let lines = input
+            .split("\n")
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<&str>>();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let mut tiles = vec!['.'; width * lines.len()];
+        for (y, line) in lines.iter().enumerate() {
+            for (x, char) in line.chars().enumerate() {
+                tiles[y * width + x] = char;
+            }
+        }
+        PipeMap {
+            width,
+            height: lines.len(),
+            tiles,
+        }
+    }
+
+    pub fn get(&self, (y, x): Point) -> char {
+        self.tiles[y * self.width + x]
+    }
+
+    pub fn set(&mut self, (y, x): Point, pipe: char) {
+        self.tiles[y * self.width + x] = pipe;
+    }
+
+    pub fn find_starting_point(&self) -> Option<Point> {
+        self.tiles
+            .iter()
+            .position(|c| *c == 'S')
+            .map(|index| (index / self.width, index % self.width))
+    }
+
+    fn neighbour(&self, (y, x): Point, direction: u8) -> Option<Point> {
+        match direction {
+            NORTH if y > 0 => Some((y - 1, x)),
+            EAST if x + 1 < self.width => Some((y, x + 1)),
+            SOUTH if y + 1 < self.height => Some((y + 1, x)),
+            WEST if x > 0 => Some((y, x - 1)),
+            _ => None,
+        }
+    }
+
+    fn connected_directions(&self, at: Point) -> u8 {
+        DIRECTIONS
             .into_iter()
-            .filter(|p| *p != last)
-            .collect::<Vec<Point>>();
-        paths.remove(0);
-        for available_point in available_points {
-            if available_point == start {
-                return this_path;
+            .filter(|direction| {
+                self.neighbour(at, *direction).is_some_and(|neighbour| {
+                    connections(self.get(neighbour)) & opposite(*direction) != 0
+                })
+            })
+            .fold(0, |mask, direction| mask | direction)
+    }
+
+    pub fn start_pipe_candidates(&self, start: Point) -> Vec<char> {
+        let connected = self.connected_directions(start);
+        PIPES
+            .into_iter()
+            .filter(|pipe| connections(*pipe) & connected == connections(*pipe))
+            .collect()
+    }
+
+    pub fn start_loops(&self, start: Point) -> Vec<(char, PipeLoop)> {
+        let mut resolved_map = self.clone();
+        self.start_pipe_candidates(start)
+            .into_iter()
+            .filter_map(|pipe| {
+                resolved_map.set(start, pipe);
+                resolved_map
+                    .trace_loop(start)
+                    .map(|pipe_loop| (pipe, pipe_loop))
+            })
+            .collect()
+    }
+
+    pub fn resolve_start(&mut self) -> Option<(Point, char)> {
+        let start = self.find_starting_point()?;
+        let (pipe, _) = self
+            .start_loops(start)
+            .into_iter()
+            .max_by_key(|(_, pipe_loop)| pipe_loop.length())?;
+        self.set(start, pipe);
+        Some((start, pipe))
+    }
+
+    pub fn trace_loop(&self, start: Point) -> Option<PipeLoop> {
+        self.walk_loop(start, &mut vec![false; self.tiles.len()])
+    }
+
+    fn walk_loop(&self, start: Point, visited: &mut [bool]) -> Option<PipeLoop> {
+        let mut tiles = vec![start];
+        let mut came_from = 0;
+        let mut at = start;
+        loop {
+            visited[at.0 * self.width + at.1] = true;
+            let pipe_connections = connections(self.get(at));
+            let direction = DIRECTIONS
+                .into_iter()
+                .find(|direction| pipe_connections & direction != 0 && *direction != came_from)?;
+            let next = self.neighbour(at, direction)?;
+            if connections(self.get(next)) & opposite(direction) == 0 {
+                return None;
             }
-            if this_path.contains(&available_point) {
+            if next == start {
+                return Some(PipeLoop { tiles });
+            }
+            if visited[next.0 * self.width + next.1] {
+                return None;
+            }
+            tiles.push(next);
+            came_from = opposite(direction);
+            at = next;
+        }
+    }
+
+    pub fn find_loops(&self) -> Vec<PipeLoop> {
+        let mut resolved_map = self.clone();
+        resolved_map.resolve_start();
+        let mut visited = vec![false; self.tiles.len()];
+        let mut pipe_loops = Vec::new();
+        for index in 0..self.tiles.len() {
+            if visited[index] || connections(resolved_map.tiles[index]) == 0 {
                 continue;
             }
-            let mut new_path = this_path.clone();
-            new_path.push(available_point);
-            paths.push(new_path);
+            let start = (index / self.width, index % self.width);
+            if let Some(pipe_loop) = resolved_map.walk_loop(start, &mut visited) {
+                pipe_loops.push(pipe_loop);
+            }
         }
+        pipe_loops
     }
 }
 
-fn solve_part_one() -> i32 {
-    let mut pipe_map = parse_map();
-    let start = find_starting_point(&pipe_map);
-    pipe_map[start.0][start.1] = find_start_pipe_type(&pipe_map, start);
-    let pipe_loop = find_loop(&pipe_map, start);
-    return (pipe_loop.len() / 2) as i32;
+fn parse_map() -> PipeMap {
+    PipeMap::parse(&std::fs::read_to_string("input/day10").unwrap())
 }
 
-fn solve_part_two() -> i32 {
+fn find_main_loop() -> PipeLoop {
     let mut pipe_map = parse_map();
-    let start = find_starting_point(&pipe_map);
-    pipe_map[start.0][start.1] = find_start_pipe_type(&pipe_map, start);
-    let pipe_loop = find_loop(&pipe_map, start);
-    let mut enclosed = Vec::new();
-
-    pipe_map.into_iter().enumerate().for_each(|(y, row)| {
-        let mut in_the_loop = false;
-        let mut switch_pipes = ['|', 'L', 'F'].to_vec();
-        row.into_iter().enumerate().for_each(|(x, char)| {
-            if pipe_loop.contains(&(y, x)) && switch_pipes.contains(&char) {
-                in_the_loop = !in_the_loop;
-                switch_pipes = match char {
-                    c if c == 'F' => ['F', '7', '|'].to_vec(),
-                    c if c == 'L' => ['L', 'J', '|'].to_vec(),
-                    c if ['|', 'J', '7'].contains(&c) => ['|', 'L', 'F'].to_vec(),
-                    _ => panic!("Invalid character {}", char),
-                }
-            } else if !pipe_loop.contains(&(y, x)) && in_the_loop {
-                enclosed.push((y, x));
-            }
-        })
-    });
-    return enclosed.len() as i32;
+    let (start, _) = pipe_map.resolve_start().unwrap();
+    pipe_map.trace_loop(start).unwrap()
+}
+
+fn solve_part_one() -> usize {
+    find_main_loop().farthest_distance()
+}
+
+fn solve_part_two() -> usize {
+    find_main_loop().enclosed_tiles()
 }
 
 pub fn solve() {
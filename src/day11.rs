@@ -1,97 +1,151 @@
-fn parse_star_map() -> Vec<(i64, i64)> {
-    let file = std::fs::read_to_string("input/day11").unwrap();
-    file.split("\n")
-        .filter(|s| !s.is_empty())
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.chars()
-                .enumerate()
-                .filter_map(|(column, char)| {
-                    if char == '#' {
-                        Some((row as i64, column as i64))
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<(i64, i64)>>()
-        })
-        .collect::<Vec<(i64, i64)>>()
+use std::collections::BTreeSet;
+
+pub type Galaxy = (i64, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GalaxyPair {
+    pub first: usize,
+    pub second: usize,
+    pub distance: i64,
 }
 
-fn get_max_y(star_map: &Vec<(i64, i64)>) -> i64 {
-    star_map.iter().map(|(y, _)| *y).max().unwrap()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StarMap {
+    pub galaxies: Vec<Galaxy>,
 }
 
-fn get_max_x(star_map: &Vec<(i64, i64)>) -> i64 {
-    star_map.iter().map(|(_, x)| *x).max().unwrap()
+fn get_manhattan_distance(star1: &Galaxy, star2: &Galaxy) -> i64 {
+    (star1.0 - star2.0).abs() + (star1.1 - star2.1).abs()
 }
 
-fn get_manhattan_distance(star1: &(i64, i64), star2: &(i64, i64)) -> i64 {
-    return (star1.0 - star2.0).abs() + (star1.1 - star2.1).abs();
+fn expand_axis(coordinates: &[i64], factor: i64) -> Vec<i64> {
+    let mut occupied = coordinates.to_vec();
+    occupied.sort_unstable();
+    occupied.dedup();
+    coordinates
+        .iter()
+        .map(|coordinate| {
+            let empty_before = coordinate - occupied.partition_point(|c| c < coordinate) as i64;
+            coordinate + empty_before * (factor - 1)
+        })
+        .collect()
 }
 
-fn expand_universe(star_map: &Vec<(i64, i64)>, expand_by: i64) -> Vec<(i64, i64)> {
-    let mut expanded_star_map = star_map.clone();
-    let mut y = 0;
-    while y < get_max_y(&expanded_star_map) {
-        if expanded_star_map.iter().all(|star| star.0 != y) {
-            expanded_star_map = expanded_star_map
-                .iter()
-                .map(|star| {
-                    if star.0 > y {
-                        (star.0 + expand_by, star.1)
-                    } else {
-                        *star
-                    }
+fn sum_axis_distances(coordinates: &mut [i64]) -> i128 {
+    coordinates.sort_unstable();
+    let mut prefix_sum = 0i128;
+    let mut total = 0i128;
+    for (index, coordinate) in coordinates.iter().enumerate() {
+        total += *coordinate as i128 * index as i128 - prefix_sum;
+        prefix_sum += *coordinate as i128;
+    }
+    total
+}
+
+impl StarMap {
+    pub fn parse(input: &str) -> Self {
+        StarMap {
+            galaxies: input
+                .split("\n")
+                .filter(|s| !s.is_empty())
+                .enumerate()
+                .flat_map(|(row, line)| {
+                    line.chars()
+                        .enumerate()
+                        .filter(|(_, char)| *char == '#')
+                        .map(move |(column, _)| (row as i64, column as i64))
                 })
-                .collect();
-            y += expand_by
+                .collect(),
         }
-        y += 1;
     }
-    let mut x = 0;
-    while x < get_max_x(&expanded_star_map) {
-        if expanded_star_map.iter().all(|star| star.1 != x) {
-            expanded_star_map = expanded_star_map
-                .iter()
-                .map(|star| {
-                    if star.1 > x {
-                        (star.0, star.1 + expand_by)
-                    } else {
-                        *star
-                    }
-                })
-                .collect();
-            x += expand_by
+
+    pub fn expand(&self, row_factor: i64, column_factor: i64) -> StarMap {
+        let rows = self.galaxies.iter().map(|(y, _)| *y).collect::<Vec<i64>>();
+        let columns = self.galaxies.iter().map(|(_, x)| *x).collect::<Vec<i64>>();
+        StarMap {
+            galaxies: expand_axis(&rows, row_factor)
+                .into_iter()
+                .zip(expand_axis(&columns, column_factor))
+                .collect(),
+        }
+    }
+
+    pub fn sum_of_distances(&self) -> i128 {
+        let mut rows = self.galaxies.iter().map(|(y, _)| *y).collect::<Vec<i64>>();
+        let mut columns = self.galaxies.iter().map(|(_, x)| *x).collect::<Vec<i64>>();
+        sum_axis_distances(&mut rows) + sum_axis_distances(&mut columns)
+    }
+
+    fn pair(&self, first: usize, second: usize) -> GalaxyPair {
+        GalaxyPair {
+            first: first.min(second),
+            second: first.max(second),
+            distance: get_manhattan_distance(&self.galaxies[first], &self.galaxies[second]),
+        }
+    }
+
+    pub fn nearest_pair(&self) -> Option<GalaxyPair> {
+        let mut by_column = (0..self.galaxies.len()).collect::<Vec<usize>>();
+        by_column.sort_by_key(|index| (self.galaxies[*index].1, self.galaxies[*index].0));
+        let mut active: BTreeSet<(i64, usize)> = BTreeSet::new();
+        let mut oldest_active = 0;
+        let mut nearest: Option<GalaxyPair> = None;
+        for &index in &by_column {
+            let (y, x) = self.galaxies[index];
+            if let Some(best) = nearest {
+                while x - self.galaxies[by_column[oldest_active]].1 > best.distance {
+                    let old = by_column[oldest_active];
+                    active.remove(&(self.galaxies[old].0, old));
+                    oldest_active += 1;
+                }
+            }
+            let reach = nearest.map_or(i64::MAX, |best| best.distance);
+            let candidates = active
+                .range((y.saturating_sub(reach), 0)..=(y.saturating_add(reach), usize::MAX))
+                .map(|(_, other)| self.pair(index, *other))
+                .collect::<Vec<GalaxyPair>>();
+            for candidate in candidates {
+                if nearest.is_none_or(|best| candidate.distance < best.distance) {
+                    nearest = Some(candidate);
+                }
+            }
+            active.insert((y, index));
         }
-        x += 1;
+        nearest
+    }
+
+    pub fn farthest_pair(&self) -> Option<GalaxyPair> {
+        if self.galaxies.len() < 2 {
+            return None;
+        }
+        let rotations: [fn(&Galaxy) -> i64; 2] = [|(y, x)| y + x, |(y, x)| y - x];
+        rotations
+            .iter()
+            .map(|rotation| {
+                let lowest = (0..self.galaxies.len())
+                    .min_by_key(|index| rotation(&self.galaxies[*index]))
+                    .unwrap();
+                let highest = (0..self.galaxies.len())
+                    .max_by_key(|index| rotation(&self.galaxies[*index]))
+                    .unwrap();
+                self.pair(lowest, highest)
+            })
+            .max_by_key(|pair| pair.distance)
     }
-    return expanded_star_map;
 }
-fn solve_part_one() -> i64 {
-    let star_map = parse_star_map();
-    let expanded_star_map = expand_universe(&star_map, 1);
-    let mut combined_distances = 0;
-    (0..expanded_star_map.len()).for_each(|i| {
-        (i + 1..expanded_star_map.len()).for_each(|j| {
-            combined_distances +=
-                get_manhattan_distance(&expanded_star_map[i], &expanded_star_map[j]);
-        });
-    });
-    return combined_distances;
+
+fn parse_star_map() -> StarMap {
+    StarMap::parse(&std::fs::read_to_string("input/day11").unwrap())
+}
+
+fn solve_part_one() -> i128 {
+    parse_star_map().expand(2, 2).sum_of_distances()
 }
 
-fn solve_part_two() -> i64 {
-    let star_map = parse_star_map();
-    let expanded_star_map = expand_universe(&star_map, 999999);
-    let mut combined_distances = 0;
-    (0..expanded_star_map.len()).for_each(|i| {
-        (i + 1..expanded_star_map.len()).for_each(|j| {
-            combined_distances +=
-                get_manhattan_distance(&expanded_star_map[i], &expanded_star_map[j]);
-        });
-    });
-    return combined_distances;
+fn solve_part_two() -> i128 {
+    parse_star_map()
+        .expand(1_000_000, 1_000_000)
+        .sum_of_distances()
 }
 
 pub fn solve() {
@@ -1,140 +1,359 @@
-use std::collections::HashMap;
+use num_bigint::{BigUint, RandBigInt};
+use rand::Rng;
 
-fn read_condition_string(condition: &str) -> Condition {
-    condition
-        .split(",")
-        .map(|s| s.parse::<i32>().unwrap())
-        .collect()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpringRow {
+    pub springs: Vec<char>,
+    pub groups: Vec<usize>,
+}
+
+pub struct ArrangementTable<'a> {
+    springs: &'a [char],
+    groups: &'a [usize],
+    damaged_prefix: Vec<usize>,
+    operational_prefix: Vec<usize>,
+    ways: Vec<BigUint>,
 }
 
-fn recursive_find(
-    cache: &mut HashMap<(Vec<char>, i64, Vec<i32>), i64>,
-    spring_string: Vec<char>,
-    condition: Vec<i32>,
-    this_group: i32,
-) -> i64 {
-    if condition.len() == 0 {
-        return if spring_string.contains(&'#') { 0 } else { 1 };
-    }
-    if spring_string.len() == 0 && this_group == condition[0] && condition.len() == 1 {
-        return 1;
-    }
-    if spring_string.len() == 0 {
-        return 0;
-    }
-    if this_group > condition[0] {
-        return 0;
-    }
-    let cache_key = (spring_string.clone(), this_group as i64, condition.clone());
-    if cache.contains_key(&cache_key) {
-        return *cache.get(&cache_key).unwrap();
-    }
-    let matches = match spring_string[0] {
-        '#' => recursive_find(
-            cache,
-            spring_string.clone().iter().skip(1).map(|c| *c).collect(),
-            condition.clone(),
-            this_group + 1,
-        ),
-        '.' => {
-            if this_group == 0 {
-                recursive_find(
-                    cache,
-                    spring_string.clone().iter().skip(1).map(|c| *c).collect(),
-                    condition.clone(),
-                    this_group,
-                )
-            } else if this_group == condition[0] {
-                recursive_find(
-                    cache,
-                    spring_string.clone().iter().skip(1).map(|c| *c).collect(),
-                    condition.clone().iter().skip(1).map(|i| *i).collect(),
-                    0,
-                )
-            } else {
-                0
+impl<'a> ArrangementTable<'a> {
+    pub fn new(springs: &'a [char], groups: &'a [usize]) -> Self {
+        let length = springs.len();
+        let prefix_count = |wanted: char| {
+            springs.iter().fold(vec![0], |mut prefix, spring| {
+                prefix.push(prefix.last().unwrap() + (*spring == wanted) as usize);
+                prefix
+            })
+        };
+        let mut table = ArrangementTable {
+            springs,
+            groups,
+            damaged_prefix: prefix_count('#'),
+            operational_prefix: prefix_count('.'),
+            ways: vec![BigUint::ZERO; (groups.len() + 1) * (length + 1)],
+        };
+        let finished = table.index(length, groups.len());
+        table.ways[finished] = BigUint::from(1u32);
+        for position in (0..length).rev() {
+            for group in (0..=groups.len()).rev() {
+                let mut ways = BigUint::ZERO;
+                if springs[position] != '#' {
+                    ways += table.ways_from(position + 1, group);
+                }
+                if let Some(next_position) = table.after_group(position, group) {
+                    ways += table.ways_from(next_position, group + 1);
+                }
+                let index = table.index(position, group);
+                table.ways[index] = ways;
             }
         }
-        '?' => {
-            let dot_case = if this_group == 0 {
-                recursive_find(
-                    cache,
-                    spring_string.clone().iter().skip(1).map(|c| *c).collect(),
-                    condition.clone(),
-                    this_group,
-                )
-            } else if this_group == condition[0] {
-                recursive_find(
-                    cache,
-                    spring_string.clone().iter().skip(1).map(|c| *c).collect(),
-                    condition.clone().iter().skip(1).map(|i| *i).collect(),
-                    0,
-                )
-            } else {
-                0
-            };
-            dot_case
-                + recursive_find(
-                    cache,
-                    spring_string.iter().skip(1).map(|c| *c).collect(),
-                    condition.clone(),
-                    this_group + 1,
-                )
-        }
-        _ => panic!("Unknown character"),
-    };
-    cache.insert(cache_key, matches);
-    matches
-}
-
-type Condition = Vec<i32>;
-
-fn parse_spring_strings(unfold: bool) -> Vec<(Vec<char>, Condition)> {
-    let file = std::fs::read_to_string("input/day12").unwrap();
-    file.split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let split_line = line.split(" ").collect::<Vec<&str>>();
-            let mut condition = read_condition_string(split_line[1]);
-            let mut spring_string = Vec::new();
-            split_line[0].chars().for_each(|c| {
-                if spring_string.len() == 0 {
-                    spring_string.push(c)
-                } else if !(spring_string[spring_string.len() - 1] == '.' && c == '.') {
-                    spring_string.push(c);
+        table
+    }
+
+    fn index(&self, position: usize, group: usize) -> usize {
+        group * (self.springs.len() + 1) + position
+    }
+
+    pub fn ways_from(&self, position: usize, group: usize) -> &BigUint {
+        &self.ways[self.index(position, group)]
+    }
+
+    pub fn is_possible_from(&self, position: usize, group: usize) -> bool {
+        *self.ways_from(position, group) != BigUint::ZERO
+    }
+
+    pub fn can_be(&self, range: std::ops::Range<usize>, spring: char) -> bool {
+        let forbidden = match spring {
+            '#' => &self.operational_prefix,
+            _ => &self.damaged_prefix,
+        };
+        forbidden[range.end] == forbidden[range.start]
+    }
+
+    pub fn after_group(&self, position: usize, group: usize) -> Option<usize> {
+        let length = *self.groups.get(group)?;
+        let end = position + length;
+        if end > self.springs.len() || !self.can_be(position..end, '#') {
+            return None;
+        }
+        if end == self.springs.len() {
+            return Some(end);
+        }
+        if self.springs[end] == '#' {
+            return None;
+        }
+        Some(end + 1)
+    }
+
+    pub fn springs(&self) -> &[char] {
+        self.springs
+    }
+
+    pub fn groups(&self) -> &[usize] {
+        self.groups
+    }
+
+    pub fn count(&self) -> BigUint {
+        self.ways_from(0, 0).clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellOptions {
+    pub can_be_damaged: Vec<bool>,
+    pub can_be_operational: Vec<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub forced_damaged: Vec<usize>,
+    pub forced_operational: Vec<usize>,
+}
+
+struct Frame {
+    position: usize,
+    group: usize,
+    next_option: u8,
+}
+
+pub struct Arrangements<'t, 'a> {
+    table: &'t ArrangementTable<'a>,
+    stack: Vec<Frame>,
+    current: Vec<char>,
+}
+
+impl<'t, 'a> Arrangements<'t, 'a> {
+    fn push_group(&mut self, position: usize, group: usize) -> bool {
+        match self.table.after_group(position, group) {
+            Some(next_position) if self.table.is_possible_from(next_position, group + 1) => {
+                let end = position + self.table.groups[group];
+                self.current.resize(end, '#');
+                self.current.resize(next_position, '.');
+                self.stack.push(Frame {
+                    position: next_position,
+                    group: group + 1,
+                    next_option: 0,
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn push_operational(&mut self, position: usize, group: usize) -> bool {
+        if self.table.springs[position] == '#' || !self.table.is_possible_from(position + 1, group)
+        {
+            return false;
+        }
+        self.current.push('.');
+        self.stack.push(Frame {
+            position: position + 1,
+            group,
+            next_option: 0,
+        });
+        true
+    }
+}
+
+impl Iterator for Arrangements<'_, '_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while let Some(frame) = self.stack.last_mut() {
+            let (position, group) = (frame.position, frame.group);
+            if position == self.table.springs.len() {
+                self.stack.pop();
+                return Some(self.current.iter().collect());
+            }
+            let option = frame.next_option;
+            frame.next_option += 1;
+            self.current.truncate(position);
+            match option {
+                0 => {
+                    self.push_group(position, group);
                 }
+                1 => {
+                    self.push_operational(position, group);
+                }
+                _ => {
+                    self.stack.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'a> ArrangementTable<'a> {
+    pub fn arrangements(&self) -> Arrangements<'_, 'a> {
+        let mut stack = Vec::new();
+        if self.is_possible_from(0, 0) {
+            stack.push(Frame {
+                position: 0,
+                group: 0,
+                next_option: 0,
             });
-            if unfold {
-                let repeat = spring_string.clone();
-                (0..4).for_each(|_| {
-                    spring_string.push('?');
-                    spring_string.extend(repeat.clone());
-                });
-                let condition_repeat = condition.clone();
-                (0..4).for_each(|_| condition.extend(condition_repeat.clone()));
+        }
+        Arrangements {
+            table: self,
+            stack,
+            current: Vec::with_capacity(self.springs.len()),
+        }
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<String> {
+        if !self.is_possible_from(0, 0) {
+            return None;
+        }
+        let mut remaining = rng.gen_biguint_below(self.ways_from(0, 0));
+        let mut arrangement = String::with_capacity(self.springs.len());
+        let (mut position, mut group) = (0, 0);
+        while position < self.springs.len() {
+            if let Some(next_position) = self.after_group(position, group) {
+                let group_ways = self.ways_from(next_position, group + 1);
+                if remaining < *group_ways {
+                    let end = position + self.groups[group];
+                    arrangement.extend(std::iter::repeat_n('#', end - position));
+                    arrangement.extend(std::iter::repeat_n('.', next_position - end));
+                    position = next_position;
+                    group += 1;
+                    continue;
+                }
+                remaining -= group_ways;
+            }
+            arrangement.push('.');
+            position += 1;
+        }
+        Some(arrangement)
+    }
+
+    pub fn cell_options(&self) -> Option<CellOptions> {
+        if !self.is_possible_from(0, 0) {
+            return None;
+        }
+        let length = self.springs.len();
+        let mut reachable = vec![false; self.ways.len()];
+        let mut damaged_cover = vec![0i64; length + 1];
+        let mut can_be_operational = vec![false; length];
+        reachable[self.index(0, 0)] = true;
+        for position in 0..length {
+            for group in 0..=self.groups.len() {
+                if !reachable[self.index(position, group)]
+                    || !self.is_possible_from(position, group)
+                {
+                    continue;
+                }
+                if self.springs[position] != '#' && self.is_possible_from(position + 1, group) {
+                    can_be_operational[position] = true;
+                    let next = self.index(position + 1, group);
+                    reachable[next] = true;
+                }
+                if let Some(next_position) = self.after_group(position, group) {
+                    if self.is_possible_from(next_position, group + 1) {
+                        let end = position + self.groups[group];
+                        damaged_cover[position] += 1;
+                        damaged_cover[end] -= 1;
+                        if next_position > end {
+                            can_be_operational[end] = true;
+                        }
+                        let next = self.index(next_position, group + 1);
+                        reachable[next] = true;
+                    }
+                }
             }
-            (spring_string, condition)
+        }
+        let can_be_damaged = damaged_cover
+            .iter()
+            .take(length)
+            .scan(0, |covering, change| {
+                *covering += change;
+                Some(*covering > 0)
+            })
+            .collect();
+        Some(CellOptions {
+            can_be_damaged,
+            can_be_operational,
         })
-        .collect()
+    }
+
+    pub fn explain(&self) -> Option<Explanation> {
+        let cell_options = self.cell_options()?;
+        let forced = |can_be: &[bool], cannot_be: &[bool]| {
+            (0..self.springs.len())
+                .filter(|position| can_be[*position] && !cannot_be[*position])
+                .collect::<Vec<usize>>()
+        };
+        Some(Explanation {
+            forced_damaged: forced(
+                &cell_options.can_be_damaged,
+                &cell_options.can_be_operational,
+            ),
+            forced_operational: forced(
+                &cell_options.can_be_operational,
+                &cell_options.can_be_damaged,
+            ),
+        })
+    }
 }
 
-fn solve_part_one() -> Vec<i64> {
-    let mut cache = HashMap::new();
-    parse_spring_strings(false)
-        .into_iter()
-        .map(|(spring_string, condition)| recursive_find(&mut cache, spring_string, condition, 0))
-        .collect()
+impl SpringRow {
+    pub fn parse(line: &str) -> Self {
+        let (springs, groups) = line.split_once(" ").unwrap();
+        SpringRow {
+            springs: springs.chars().collect(),
+            groups: groups
+                .split(",")
+                .map(|s| s.parse::<usize>().unwrap())
+                .collect(),
+        }
+    }
+
+    pub fn unfold(&self, factor: usize) -> SpringRow {
+        let mut springs = Vec::with_capacity((self.springs.len() + 1) * factor);
+        for copy in 0..factor {
+            if copy > 0 {
+                springs.push('?');
+            }
+            springs.extend_from_slice(&self.springs);
+        }
+        SpringRow {
+            springs,
+            groups: self.groups.repeat(factor),
+        }
+    }
+
+    pub fn arrangement_table(&self) -> ArrangementTable<'_> {
+        ArrangementTable::new(&self.springs, &self.groups)
+    }
+
+    pub fn count_arrangements(&self) -> BigUint {
+        self.arrangement_table().count()
+    }
 }
 
-fn solve_part_two() -> Vec<i64> {
-    let mut cache = HashMap::new();
-    parse_spring_strings(true)
-        .into_iter()
-        .map(|(spring_string, condition)| recursive_find(&mut cache, spring_string, condition, 0))
+pub fn parse_spring_rows(input: &str) -> Vec<SpringRow> {
+    input
+        .split("\n")
+        .filter(|line| !line.is_empty())
+        .map(SpringRow::parse)
         .collect()
 }
 
+fn total_arrangements(unfold_factor: usize) -> BigUint {
+    parse_spring_rows(&std::fs::read_to_string("input/day12").unwrap())
+        .iter()
+        .map(|spring_row| spring_row.unfold(unfold_factor).count_arrangements())
+        .sum()
+}
+
+fn solve_part_one() -> BigUint {
+    total_arrangements(1)
+}
+
+fn solve_part_two() -> BigUint {
+    total_arrangements(5)
+}
+
 pub fn solve() {
-    println!("Part 1: {}", solve_part_one().into_iter().sum::<i64>());
-    println!("Part 2: {}", solve_part_two().into_iter().sum::<i64>());
+    println!("Part 1: {}", solve_part_one());
+    println!("Part 2: {}", solve_part_two());
 }
@@ -1,111 +1,377 @@
-type Pattern = Vec<Vec<char>>;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    pub width: usize,
+    pub height: usize,
+    rows: Vec<u128>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reflection {
+    pub orientation: Orientation,
+    pub position: usize,
+    pub smudges: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePatternError {
+    TooWide(usize),
+    TooTall(usize),
+    RaggedRows,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    QuarterTurn,
+    HalfTurn,
+    ThreeQuarterTurn,
+    HorizontalMirror,
+    VerticalMirror,
+    DiagonalMirror,
+    AntiDiagonalMirror,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymmetryGroup {
+    pub elements: Vec<Symmetry>,
+    pub corrections: usize,
+}
+
+const SUBGROUPS: [&[Symmetry]; 10] = {
+    use Symmetry::*;
+    [
+        &[
+            Identity,
+            QuarterTurn,
+            HalfTurn,
+            ThreeQuarterTurn,
+            HorizontalMirror,
+            VerticalMirror,
+            DiagonalMirror,
+            AntiDiagonalMirror,
+        ],
+        &[Identity, QuarterTurn, HalfTurn, ThreeQuarterTurn],
+        &[Identity, HalfTurn, HorizontalMirror, VerticalMirror],
+        &[Identity, HalfTurn, DiagonalMirror, AntiDiagonalMirror],
+        &[Identity, HalfTurn],
+        &[Identity, HorizontalMirror],
+        &[Identity, VerticalMirror],
+        &[Identity, DiagonalMirror],
+        &[Identity, AntiDiagonalMirror],
+        &[Identity],
+    ]
+};
 
-trait Transposable {
+pub trait Transposable {
     fn transpose(&self) -> Self;
+    fn flip_vertical(&self) -> Self;
+    fn flip_horizontal(&self) -> Self;
+
+    fn rotate_clockwise(&self) -> Self
+    where
+        Self: Sized,
+    {
+        self.transpose().flip_horizontal()
+    }
+
+    fn rotate_half(&self) -> Self
+    where
+        Self: Sized,
+    {
+        self.flip_vertical().flip_horizontal()
+    }
+
+    fn rotate_counterclockwise(&self) -> Self
+    where
+        Self: Sized,
+    {
+        self.transpose().flip_vertical()
+    }
+
+    fn anti_transpose(&self) -> Self
+    where
+        Self: Sized,
+    {
+        self.transpose().rotate_half()
+    }
+
+    fn transformed(&self, symmetry: Symmetry) -> Self
+    where
+        Self: Sized + Clone,
+    {
+        match symmetry {
+            Symmetry::Identity => self.clone(),
+            Symmetry::QuarterTurn => self.rotate_clockwise(),
+            Symmetry::HalfTurn => self.rotate_half(),
+            Symmetry::ThreeQuarterTurn => self.rotate_counterclockwise(),
+            Symmetry::HorizontalMirror => self.flip_vertical(),
+            Symmetry::VerticalMirror => self.flip_horizontal(),
+            Symmetry::DiagonalMirror => self.transpose(),
+            Symmetry::AntiDiagonalMirror => self.anti_transpose(),
+        }
+    }
 }
 
 impl Transposable for Pattern {
     fn transpose(&self) -> Self {
-        (0..self[0].len())
-            .map(|x| (0..self.len()).map(|y| self[y][x]).collect::<Vec<char>>())
+        Pattern {
+            width: self.height,
+            height: self.width,
+            rows: (0..self.width)
+                .map(|x| {
+                    (0..self.height)
+                        .filter(|y| self.is_rock(*y, x))
+                        .fold(0, |row, y| row | 1 << y)
+                })
+                .collect(),
+        }
+    }
+
+    fn flip_vertical(&self) -> Self {
+        Pattern {
+            rows: self.rows.iter().rev().copied().collect(),
+            ..self.clone()
+        }
+    }
+
+    fn flip_horizontal(&self) -> Self {
+        let unused_bits = u128::BITS as usize - self.width;
+        Pattern {
+            rows: self
+                .rows
+                .iter()
+                .map(|row| {
+                    row.reverse_bits()
+                        .checked_shr(unused_bits as u32)
+                        .unwrap_or(0)
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl<T: Clone> Transposable for Vec<Vec<T>> {
+    fn transpose(&self) -> Self {
+        let width = self.first().map_or(0, |row| row.len());
+        (0..width)
+            .map(|x| self.iter().map(|row| row[x].clone()).collect())
+            .collect()
+    }
+
+    fn flip_vertical(&self) -> Self {
+        self.iter().rev().cloned().collect()
+    }
+
+    fn flip_horizontal(&self) -> Self {
+        self.iter()
+            .map(|row| row.iter().rev().cloned().collect())
             .collect()
     }
 }
 
-trait PatternLineComparison {
-    fn one_off(&self, other: &Self) -> bool;
+impl Symmetry {
+    pub fn needs_square(&self) -> bool {
+        matches!(
+            self,
+            Symmetry::QuarterTurn
+                | Symmetry::ThreeQuarterTurn
+                | Symmetry::DiagonalMirror
+                | Symmetry::AntiDiagonalMirror
+        )
+    }
 }
 
-impl PatternLineComparison for Vec<char> {
-    fn one_off(&self, other: &Vec<char>) -> bool {
-        let mut mismatch = 0;
-        for i in 0..self.len() {
-            if self[i] != other[i] {
-                mismatch += 1;
-            }
-            if mismatch > 1 {
-                return false;
-            }
+impl SymmetryGroup {
+    pub fn name(&self) -> &'static str {
+        let has = |symmetry| self.elements.contains(&symmetry);
+        match self.elements.len() {
+            8 => "D4",
+            4 if has(Symmetry::QuarterTurn) => "C4",
+            4 => "D2",
+            2 if has(Symmetry::HalfTurn) => "C2",
+            2 => "D1",
+            _ => "trivial",
         }
-        true
     }
 }
 
-fn lines_above_reflection_point(pattern: Pattern, repair_smudges: bool) -> i64 {
-    let reflection_points = pattern
-        .windows(2)
-        .enumerate()
-        .filter_map(|(index, window)| {
-            if window[0].one_off(&window[1]) {
-                Some(index)
-            } else {
-                None
-            }
+impl Reflection {
+    pub fn summary(&self) -> usize {
+        match self.orientation {
+            Orientation::Horizontal => 100 * self.position,
+            Orientation::Vertical => self.position,
+        }
+    }
+}
+
+impl Pattern {
+    pub fn parse(input: &str) -> Result<Self, ParsePatternError> {
+        let lines = input
+            .split("\n")
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<&str>>();
+        let width = lines.first().map_or(0, |line| line.len());
+        if width > u128::BITS as usize {
+            return Err(ParsePatternError::TooWide(width));
+        }
+        if lines.len() > u128::BITS as usize {
+            return Err(ParsePatternError::TooTall(lines.len()));
+        }
+        if lines.iter().any(|line| line.len() != width) {
+            return Err(ParsePatternError::RaggedRows);
+        }
+        Ok(Pattern {
+            width,
+            height: lines.len(),
+            rows: lines
+                .iter()
+                .map(|line| {
+                    line.chars()
+                        .enumerate()
+                        .filter(|(_, char)| *char == '#')
+                        .fold(0, |row, (x, _)| row | 1 << x)
+                })
+                .collect(),
         })
-        .filter_map(|index| {
-            let mut low_scan = index as i32;
-            let mut high_scan = index as i32 + 1;
-            let mut corrections = 0;
-            while low_scan >= 0 && high_scan < pattern.len() as i32 {
-                if pattern[low_scan as usize] != pattern[high_scan as usize] {
-                    if pattern[low_scan as usize].one_off(&pattern[high_scan as usize])
-                        && corrections == 0
-                    {
-                        corrections += 1
-                    } else {
-                        return None;
+    }
+
+    pub fn is_rock(&self, y: usize, x: usize) -> bool {
+        self.rows[y] >> x & 1 == 1
+    }
+
+    pub fn corrections_for(&self, symmetries: &[Symmetry]) -> Option<usize> {
+        if self.width != self.height && symmetries.iter().any(Symmetry::needs_square) {
+            return None;
+        }
+        let cells = (0..self.height)
+            .map(|y| (0..self.width).map(|x| y * self.width + x).collect())
+            .collect::<Vec<Vec<usize>>>();
+        let images = symmetries
+            .iter()
+            .map(|symmetry| cells.transformed(*symmetry).concat())
+            .collect::<Vec<Vec<usize>>>();
+        let mut visited = vec![false; self.width * self.height];
+        let mut corrections = 0;
+        for start in 0..visited.len() {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            let mut orbit = vec![start];
+            let mut next = 0;
+            while let Some(&cell) = orbit.get(next) {
+                next += 1;
+                for image in &images {
+                    if !visited[image[cell]] {
+                        visited[image[cell]] = true;
+                        orbit.push(image[cell]);
                     }
                 }
-                low_scan -= 1;
-                high_scan += 1;
-            }
-            return Some((corrections, index));
-        })
-        .filter(|(corrections, _)| {
-            if repair_smudges {
-                *corrections == 1
-            } else {
-                *corrections == 0
             }
-        })
-        .collect::<Vec<(usize, usize)>>();
-    if reflection_points.len() == 0 {
-        0
-    } else if reflection_points.len() > 1 {
-        panic!("Multiple reflection points found: {:?}", reflection_points);
-    } else {
-        reflection_points[0].1 as i64 + 1
+            let rocks = orbit
+                .iter()
+                .filter(|cell| self.is_rock(*cell / self.width, *cell % self.width))
+                .count();
+            corrections += rocks.min(orbit.len() - rocks);
+        }
+        Some(corrections)
+    }
+
+    pub fn has_symmetry(&self, symmetry: Symmetry, tolerance: usize) -> bool {
+        self.corrections_for(&[symmetry])
+            .is_some_and(|corrections| corrections <= tolerance)
+    }
+
+    pub fn symmetry_group(&self, tolerance: usize) -> SymmetryGroup {
+        SUBGROUPS
+            .iter()
+            .filter_map(|elements| {
+                let corrections = self.corrections_for(elements)?;
+                (corrections <= tolerance).then(|| SymmetryGroup {
+                    elements: elements.to_vec(),
+                    corrections,
+                })
+            })
+            .next()
+            .unwrap()
     }
-}
 
-fn lines_to_the_left_of_reflection_point(pattern: Pattern, repair_smudges: bool) -> i64 {
-    lines_above_reflection_point(pattern.transpose(), repair_smudges)
+    fn mirror_lines(&self, corrections: usize) -> Vec<(usize, Vec<(usize, usize)>)> {
+        (1..self.height)
+            .filter_map(|position| {
+                let mut differences = 0;
+                for offset in 0..position.min(self.height - position) {
+                    let difference =
+                        self.rows[position - 1 - offset] ^ self.rows[position + offset];
+                    differences += difference.count_ones() as usize;
+                    if differences > corrections {
+                        return None;
+                    }
+                }
+                if differences != corrections {
+                    return None;
+                }
+                let smudges = (0..position.min(self.height - position))
+                    .flat_map(|offset| {
+                        let y = position - 1 - offset;
+                        let difference = self.rows[y] ^ self.rows[position + offset];
+                        (0..self.width)
+                            .filter(move |x| difference >> x & 1 == 1)
+                            .map(move |x| (y, x))
+                    })
+                    .collect();
+                Some((position, smudges))
+            })
+            .collect()
+    }
+
+    pub fn reflections(&self, corrections: usize) -> Vec<Reflection> {
+        let horizontal = self
+            .mirror_lines(corrections)
+            .into_iter()
+            .map(|(position, smudges)| Reflection {
+                orientation: Orientation::Horizontal,
+                position,
+                smudges,
+            });
+        let vertical =
+            self.transpose()
+                .mirror_lines(corrections)
+                .into_iter()
+                .map(|(position, smudges)| Reflection {
+                    orientation: Orientation::Vertical,
+                    position,
+                    smudges: smudges.into_iter().map(|(x, y)| (y, x)).collect(),
+                });
+        horizontal.chain(vertical).collect()
+    }
 }
 
-fn solve_part(repair_smudges: bool) -> i64 {
-    let file = std::fs::read_to_string("input/day13").unwrap();
-    let patterns = file
+pub fn parse_patterns(input: &str) -> Result<Vec<Pattern>, ParsePatternError> {
+    input
         .split("\n\n")
-        .map(|line| {
-            line.split("\n")
-                .filter(|s| !s.is_empty())
-                .map(|s| s.chars().collect::<Vec<char>>())
-                .collect()
-        })
-        .collect::<Vec<Pattern>>();
-    let lines_to_the_left: i64 = patterns
-        .iter()
-        .map(|pattern| lines_to_the_left_of_reflection_point(pattern.to_vec(), repair_smudges))
-        .sum();
+        .filter(|pattern| !pattern.trim().is_empty())
+        .map(Pattern::parse)
+        .collect()
+}
 
-    let lines_above: i64 = patterns
+fn solve_part(corrections: usize) -> usize {
+    parse_patterns(&std::fs::read_to_string("input/day13").unwrap())
+        .unwrap()
         .iter()
-        .map(|pattern| lines_above_reflection_point(pattern.to_vec(), repair_smudges))
-        .sum();
-    return lines_to_the_left + (100 * lines_above);
+        .flat_map(|pattern| pattern.reflections(corrections))
+        .map(|reflection| reflection.summary())
+        .sum()
 }
 
 pub fn solve() {
-    println!("Part 1: {}", solve_part(false));
-    println!("Part 2: {}", solve_part(true));
+    println!("Part 1: {}", solve_part(0));
+    println!("Part 2: {}", solve_part(1));
 }
@@ -1,138 +1,155 @@
-use std::collections::HashMap;
+use crate::cycle::state_after;
 
-type Point = (i64, i64);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TiltProgramError {
+    UnknownDirection(char),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Platform {
+    pub width: usize,
+    pub height: usize,
+    tiles: Vec<char>,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
 
-#[derive(Clone, Copy, PartialEq)]
-enum RockType {
-    Rolling,
-    Stable,
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'N' => Some(Direction::North),
+            'E' => Some(Direction::East),
+            'S' => Some(Direction::South),
+            'W' => Some(Direction::West),
+            _ => None,
+        }
+    }
 }
 
-struct Platform {
-    rocks: HashMap<Point, RockType>,
-    bounds: Point,
+pub fn parse_tilt_program(program: &str) -> Result<Vec<Direction>, TiltProgramError> {
+    program
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| Direction::from_char(c).ok_or(TiltProgramError::UnknownDirection(c)))
+        .collect()
 }
 
 impl Platform {
-    fn from_file(file_name: &str) -> Platform {
-        let mut rocks = HashMap::new();
-        let mut max_x = 0;
-        let mut max_y = 0;
-        std::fs::read_to_string(file_name)
-            .unwrap()
-            .lines()
+    pub fn parse(input: &str) -> Self {
+        let lines = input
+            .split("\n")
             .filter(|s| !s.is_empty())
-            .enumerate()
-            .for_each(|(y, line)| {
-                max_y = if y > max_y { y } else { max_y };
-                line.chars().enumerate().for_each(|(x, c)| {
-                    max_x = if x > max_x { x } else { max_x };
-                    match c {
-                        '#' => rocks.insert((x as i64, y as i64), RockType::Stable),
-                        'O' => rocks.insert((x as i64, y as i64), RockType::Rolling),
-                        _ => Option::None,
-                    };
-                });
-            });
+            .collect::<Vec<&str>>();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let mut tiles = vec!['.'; width * lines.len()];
+        for (y, line) in lines.iter().enumerate() {
+            for (x, char) in line.chars().enumerate() {
+                if char == '#' || char == 'O' {
+                    tiles[y * width + x] = char;
+                }
+            }
+        }
         Platform {
-            rocks,
-            bounds: (max_x as i64, max_y as i64),
+            width,
+            height: lines.len(),
+            tiles,
         }
     }
 
-    fn rotate_platform_clockwise(&mut self) {
-        self.rocks = self
-            .rocks
-            .iter()
-            .map(|((x, y), rock)| ((self.bounds.1 - *y, *x), *rock))
-            .collect::<HashMap<Point, RockType>>();
-        self.bounds = (self.bounds.1, self.bounds.0);
+    fn from_file(file_name: &str) -> Platform {
+        Platform::parse(&std::fs::read_to_string(file_name).unwrap())
     }
 
-    fn tilt_platform_north(&mut self) {
-        self.rocks
-            .clone()
-            .iter()
-            .filter(|(_, rock)| **rock == RockType::Rolling)
-            .for_each(|((x, y), _)| {
-                let mut new_point = (*x, *y);
-                let mut search_point = (*x, *y - 1);
-                loop {
-                    if search_point.1 < 0 {
-                        break;
-                    }
-                    match self.rocks.get(&(search_point.0, search_point.1)) {
-                        Some(RockType::Stable) => {
-                            break;
-                        }
-                        Some(RockType::Rolling) => {
-                            search_point = (search_point.0, search_point.1 - 1);
-                        }
-                        None => {
-                            new_point = (search_point.0, search_point.1);
-                            search_point = (search_point.0, search_point.1 - 1);
-                        }
+    pub fn get(&self, y: usize, x: usize) -> char {
+        self.tiles[y * self.width + x]
+    }
+
+    fn cell_towards(&self, direction: Direction, line: usize, offset: usize) -> usize {
+        match direction {
+            Direction::North => offset * self.width + line,
+            Direction::South => (self.height - 1 - offset) * self.width + line,
+            Direction::West => line * self.width + offset,
+            Direction::East => line * self.width + self.width - 1 - offset,
+        }
+    }
+
+    pub fn tilt(&mut self, direction: Direction) {
+        let (lines, length) = match direction {
+            Direction::North | Direction::South => (self.width, self.height),
+            Direction::East | Direction::West => (self.height, self.width),
+        };
+        for line in 0..lines {
+            let mut free = 0;
+            for offset in 0..length {
+                let cell = self.cell_towards(direction, line, offset);
+                match self.tiles[cell] {
+                    '#' => free = offset + 1,
+                    'O' => {
+                        self.tiles[cell] = '.';
+                        let landing = self.cell_towards(direction, line, free);
+                        self.tiles[landing] = 'O';
+                        free += 1;
                     }
+                    _ => {}
                 }
-                self.rocks.remove(&(*x, *y));
-                self.rocks.insert(new_point, RockType::Rolling);
-            })
+            }
+        }
     }
 
-    fn run_tilt_cycle(&mut self) {
-        self.tilt_platform_north();
-        self.rotate_platform_clockwise();
-        self.tilt_platform_north();
-        self.rotate_platform_clockwise();
-        self.tilt_platform_north();
-        self.rotate_platform_clockwise();
-        self.tilt_platform_north();
-        self.rotate_platform_clockwise();
+    pub fn run_program(&mut self, program: &[Direction], repetitions: usize) {
+        let run_once = |platform: &Platform| {
+            let mut platform = platform.clone();
+            for direction in program {
+                platform.tilt(*direction);
+            }
+            platform
+        };
+        *self = state_after(self.clone(), run_once, repetitions);
     }
-}
 
-fn get_north_load(rocks: &HashMap<Point, RockType>, bounds: &Point) -> i64 {
-    rocks
-        .iter()
-        .filter(|(_, rock)| **rock == RockType::Rolling)
-        .map(|((_, y), _)| (bounds.1 + 1) as i64 - *y)
-        .sum()
+    pub fn load(&self, edge: Direction) -> usize {
+        (0..self.tiles.len())
+            .filter(|index| self.tiles[*index] == 'O')
+            .map(|index| {
+                let (y, x) = (index / self.width, index % self.width);
+                match edge {
+                    Direction::North => self.height - y,
+                    Direction::East => x + 1,
+                    Direction::South => y + 1,
+                    Direction::West => self.width - x,
+                }
+            })
+            .sum()
+    }
+
+    pub fn loads(&self) -> [(Direction, usize); 4] {
+        Direction::ALL.map(|edge| (edge, self.load(edge)))
+    }
 }
 
-fn solve_part_one() -> i64 {
+fn solve_part_one() -> usize {
     let mut platform = Platform::from_file("input/day14");
-    platform.tilt_platform_north();
-    get_north_load(&platform.rocks, &platform.bounds)
+    platform.tilt(Direction::North);
+    platform.load(Direction::North)
 }
 
-fn solve_part_two() -> i64 {
+fn solve_part_two() -> usize {
     let mut platform = Platform::from_file("input/day14");
-    let mut states = Vec::new();
-    loop {
-        platform.run_tilt_cycle();
-        if states.contains(&platform.rocks) {
-            break;
-        }
-        states.push(platform.rocks.clone());
-    }
-    let loop_index = states
-        .iter()
-        .position(|state| state == &platform.rocks)
-        .unwrap();
-    let loop_len = states.len() - loop_index;
-    let leftover = (1000000000 - states.len() as i64) % loop_len as i64;
-    states
-        .iter()
-        .skip(loop_index)
-        .enumerate()
-        .filter_map(|(i, state)| {
-            if i as i64 == leftover - 1 {
-                Some(get_north_load(state, &platform.bounds))
-            } else {
-                None
-            }
-        })
-        .sum::<i64>()
+    platform.run_program(&parse_tilt_program("NWSE").unwrap(), 1_000_000_000);
+    platform.load(Direction::North)
 }
 
 pub fn solve() {
@@ -1,56 +1,266 @@
-use std::collections::HashMap;
-
-#[derive(PartialEq)]
-enum Operator {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operator {
     Set(i32),
     Delete,
 }
 
-struct Instruction {
-    label: String,
-    op: Operator,
-    instruction_hash: i32,
-    label_hash: i32,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub label: String,
+    pub op: Operator,
+    pub instruction_hash: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LensChange {
+    Inserted {
+        box_number: usize,
+        focal: i32,
+    },
+    Replaced {
+        box_number: usize,
+        from: i32,
+        to: i32,
+    },
+    Removed {
+        box_number: usize,
+        focal: i32,
+    },
+    Unchanged,
 }
 
-#[derive(Debug)]
-struct Box {
-    contents: Vec<(String, i32)>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoxDiff<V> {
+    pub box_number: usize,
+    pub before: Vec<(String, V)>,
+    pub after: Vec<(String, V)>,
 }
 
-impl Box {
-    fn new() -> Box {
-        Box {
-            contents: Vec::new(),
+#[derive(Debug, Clone)]
+pub struct ReplayStep {
+    pub instruction: Instruction,
+    pub change: LensChange,
+}
+
+#[derive(Debug, Clone)]
+pub struct Replay {
+    steps: Vec<ReplayStep>,
+    checkpoints: Vec<HolidayHashMap<i32>>,
+}
+
+const BOX_COUNT: usize = 256;
+const CHECKPOINT_INTERVAL: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HolidayHashMap<V> {
+    boxes: Vec<Vec<(String, V)>>,
+    len: usize,
+}
+
+impl<V> HolidayHashMap<V> {
+    pub fn new() -> Self {
+        HolidayHashMap {
+            boxes: (0..BOX_COUNT).map(|_| Vec::new()).collect(),
+            len: 0,
         }
     }
 
-    fn set(&mut self, label: String, amount: i32) {
-        if self.contents.iter().any(|(l, _)| l == &label.to_string()) {
-            self.contents = self
-                .contents
-                .iter()
-                .map(|(l, a)| {
-                    if l == &label {
-                        (l.to_string(), amount)
-                    } else {
-                        (l.to_string(), *a)
-                    }
+    fn slot(&self, label: &str) -> (usize, Option<usize>) {
+        let box_number = hash_algorithm(label) as usize;
+        let position = self.boxes[box_number].iter().position(|(l, _)| l == label);
+        (box_number, position)
+    }
+
+    pub fn get(&self, label: &str) -> Option<&V> {
+        let (box_number, position) = self.slot(label);
+        position.map(|position| &self.boxes[box_number][position].1)
+    }
+
+    pub fn insert(&mut self, label: String, value: V) -> Option<V> {
+        match self.slot(&label) {
+            (box_number, Some(position)) => Some(std::mem::replace(
+                &mut self.boxes[box_number][position].1,
+                value,
+            )),
+            (box_number, None) => {
+                self.boxes[box_number].push((label, value));
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, label: &str) -> Option<V> {
+        let (box_number, position) = self.slot(label);
+        let (_, value) = self.boxes[box_number].remove(position?);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn lenses_in_box(&self, box_number: usize) -> &[(String, V)] {
+        &self.boxes[box_number]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &str, &V)> + '_ {
+        self.boxes
+            .iter()
+            .enumerate()
+            .flat_map(|(box_number, lenses)| {
+                lenses
+                    .iter()
+                    .map(move |(label, value)| (box_number, label.as_str(), value))
+            })
+    }
+}
+
+impl<V: Clone + PartialEq> HolidayHashMap<V> {
+    pub fn diff(&self, after: &HolidayHashMap<V>) -> Vec<BoxDiff<V>> {
+        self.boxes
+            .iter()
+            .zip(&after.boxes)
+            .enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(box_number, (before, after))| BoxDiff {
+                box_number,
+                before: before.clone(),
+                after: after.clone(),
+            })
+            .collect()
+    }
+}
+
+impl<V> Default for HolidayHashMap<V> {
+    fn default() -> Self {
+        HolidayHashMap::new()
+    }
+}
+
+impl<V> FromIterator<(String, V)> for HolidayHashMap<V> {
+    fn from_iter<I: IntoIterator<Item = (String, V)>>(iter: I) -> Self {
+        let mut map = HolidayHashMap::new();
+        for (label, value) in iter {
+            map.insert(label, value);
+        }
+        map
+    }
+}
+
+impl<V: Copy + Into<i64>> HolidayHashMap<V> {
+    pub fn focusing_power(&self) -> i64 {
+        self.boxes
+            .iter()
+            .enumerate()
+            .flat_map(|(box_number, lenses)| {
+                lenses.iter().enumerate().map(move |(slot, (_, focal))| {
+                    (box_number as i64 + 1) * (slot as i64 + 1) * (*focal).into()
                 })
-                .collect();
-        } else {
-            self.contents.push((label, amount));
+            })
+            .sum()
+    }
+}
+
+impl<V: std::fmt::Display> std::fmt::Display for HolidayHashMap<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (box_number, lenses) in self.boxes.iter().enumerate() {
+            if lenses.is_empty() {
+                continue;
+            }
+            write!(f, "Box {}:", box_number)?;
+            for (label, value) in lenses {
+                write!(f, " [{} {}]", label, value)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Instruction {
+    pub fn apply(&self, boxes: &mut HolidayHashMap<i32>) -> LensChange {
+        let box_number = hash_algorithm(&self.label) as usize;
+        match self.op {
+            Operator::Set(focal) => match boxes.insert(self.label.clone(), focal) {
+                Some(from) => LensChange::Replaced {
+                    box_number,
+                    from,
+                    to: focal,
+                },
+                None => LensChange::Inserted { box_number, focal },
+            },
+            Operator::Delete => match boxes.remove(&self.label) {
+                Some(focal) => LensChange::Removed { box_number, focal },
+                None => LensChange::Unchanged,
+            },
+        }
+    }
+}
+
+impl Replay {
+    pub fn new(instructions: &[Instruction]) -> Self {
+        let mut boxes = HolidayHashMap::new();
+        let mut checkpoints = Vec::new();
+        let mut steps = Vec::with_capacity(instructions.len());
+        for (step, instruction) in instructions.iter().enumerate() {
+            if step % CHECKPOINT_INTERVAL == 0 {
+                checkpoints.push(boxes.clone());
+            }
+            steps.push(ReplayStep {
+                instruction: instruction.clone(),
+                change: instruction.apply(&mut boxes),
+            });
+        }
+        if steps.len() % CHECKPOINT_INTERVAL == 0 {
+            checkpoints.push(boxes);
+        }
+        Replay { steps, checkpoints }
+    }
+
+    pub fn steps(&self) -> &[ReplayStep] {
+        &self.steps
+    }
+
+    fn boxes_after_steps(&self, count: usize) -> HolidayHashMap<i32> {
+        let checkpoint = count / CHECKPOINT_INTERVAL;
+        let mut boxes = self.checkpoints[checkpoint].clone();
+        for replay_step in &self.steps[checkpoint * CHECKPOINT_INTERVAL..count] {
+            replay_step.instruction.apply(&mut boxes);
         }
+        boxes
+    }
+
+    pub fn snapshot_before(&self, step: usize) -> Option<HolidayHashMap<i32>> {
+        (step <= self.steps.len()).then(|| self.boxes_after_steps(step))
+    }
+
+    pub fn snapshot_after(&self, step: usize) -> Option<HolidayHashMap<i32>> {
+        (step < self.steps.len()).then(|| self.boxes_after_steps(step + 1))
+    }
+
+    pub fn diff(&self, step: usize) -> Option<Vec<BoxDiff<i32>>> {
+        let mut boxes = self.snapshot_before(step)?;
+        let before = boxes.clone();
+        self.steps.get(step)?.instruction.apply(&mut boxes);
+        Some(before.diff(&boxes))
     }
 
-    fn delete(&mut self, label: &str) {
-        self.contents = self
-            .contents
-            .clone()
+    pub fn history_of(&self, label: &str) -> Vec<(usize, &LensChange)> {
+        self.steps
             .iter()
-            .filter(|(l, _)| l != label)
-            .map(|(l, a)| (l.to_string(), *a))
-            .collect();
+            .enumerate()
+            .filter(|(_, replay_step)| {
+                replay_step.instruction.label == label
+                    && replay_step.change != LensChange::Unchanged
+            })
+            .map(|(step, replay_step)| (step, &replay_step.change))
+            .collect()
     }
 }
 
@@ -63,10 +273,9 @@ fn hash_algorithm(input_string: &str) -> i32 {
     return hash;
 }
 
-fn parse_instructions() -> Vec<Instruction> {
-    let file = std::fs::read_to_string("input/day15").unwrap();
-    file.strip_suffix("\n")
-        .unwrap()
+pub fn parse_instruction_sequence(input: &str) -> Vec<Instruction> {
+    input
+        .trim_end_matches("\n")
         .split(",")
         .map(|step| {
             let separator = if step.contains("=") { '=' } else { '-' };
@@ -82,13 +291,16 @@ fn parse_instructions() -> Vec<Instruction> {
                 } else {
                     Operator::Delete
                 },
-                label_hash: hash_algorithm(&label),
                 instruction_hash: hash_algorithm(step),
             }
         })
         .collect()
 }
 
+fn parse_instructions() -> Vec<Instruction> {
+    parse_instruction_sequence(&std::fs::read_to_string("input/day15").unwrap())
+}
+
 fn solve_part_one() -> i32 {
     parse_instructions()
         .iter()
@@ -96,40 +308,12 @@ fn solve_part_one() -> i32 {
         .sum()
 }
 
-fn solve_part_two() -> i32 {
-    let mut boxes: HashMap<i32, Box> = HashMap::new();
-    parse_instructions()
-        .iter()
-        .for_each(|instruction| match instruction.op {
-            Operator::Set(value) => {
-                if !boxes.contains_key(&instruction.label_hash) {
-                    boxes.insert(instruction.label_hash, Box::new());
-                }
-                boxes
-                    .get_mut(&instruction.label_hash)
-                    .unwrap()
-                    .set(instruction.label.clone(), value);
-            }
-            Operator::Delete => {
-                if boxes.contains_key(&instruction.label_hash) {
-                    boxes
-                        .get_mut(&instruction.label_hash)
-                        .unwrap()
-                        .delete(&instruction.label);
-                }
-            }
-        });
-    boxes
-        .iter()
-        .map(|(hash, this_box)| {
-            this_box
-                .contents
-                .iter()
-                .enumerate()
-                .map(|(index, (_, amount))| (hash + 1) * (index as i32 + 1) * amount)
-                .sum::<i32>()
-        })
-        .sum()
+fn solve_part_two() -> i64 {
+    let mut boxes = HolidayHashMap::new();
+    parse_instructions().iter().for_each(|instruction| {
+        instruction.apply(&mut boxes);
+    });
+    boxes.focusing_power()
 }
 
 pub fn solve() {
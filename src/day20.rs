@@ -1,6 +1,7 @@
-use super::day08::lowest_common_multiple;
 use std::collections::HashMap;
 
+use crate::number_theory::lcm_of;
+
 #[derive(Debug, Clone)]
 struct Broadcaster {
     outputs: Vec<String>,
@@ -179,7 +180,7 @@ fn solve_part_one() -> i64 {
     low_signal_count as i64 * high_signal_count as i64
 }
 
-fn solve_part_two() -> i64 {
+fn solve_part_two() -> i128 {
     let mut modules = parse_modules();
     let mut button_presses = 0;
     let mut component_loops = vec![];
@@ -204,7 +205,7 @@ fn solve_part_two() -> i64 {
             signals = new_signals;
         }
     }
-    lowest_common_multiple(component_loops)
+    lcm_of(&component_loops).unwrap()
 }
 
 pub fn solve() {
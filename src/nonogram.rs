@@ -0,0 +1,327 @@
+use crate::day12::ArrangementTable;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nonogram {
+    pub rows: Vec<Vec<usize>>,
+    pub columns: Vec<Vec<usize>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<char>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCluesError {
+    ClueOutsideSection { line_number: usize },
+    InvalidClue { line_number: usize, clue: String },
+    MissingSection(&'static str),
+}
+
+enum Line {
+    Row(usize),
+    Column(usize),
+}
+
+impl Grid {
+    fn new(width: usize, height: usize) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec!['?'; width * height],
+        }
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> char {
+        self.cells[row * self.width + column]
+    }
+
+    pub fn is_solved(&self) -> bool {
+        !self.cells.contains(&'?')
+    }
+
+    fn line(&self, line: &Line) -> Vec<char> {
+        match line {
+            Line::Row(row) => self.cells[row * self.width..(row + 1) * self.width].to_vec(),
+            Line::Column(column) => (0..self.height).map(|row| self.get(row, *column)).collect(),
+        }
+    }
+
+    fn set_line(&mut self, line: &Line, cells: &[char]) -> Vec<Line> {
+        let mut changed = Vec::new();
+        for (offset, cell) in cells.iter().enumerate() {
+            let (row, column) = match line {
+                Line::Row(row) => (*row, offset),
+                Line::Column(column) => (offset, *column),
+            };
+            let index = row * self.width + column;
+            if self.cells[index] != *cell {
+                self.cells[index] = *cell;
+                changed.push(match line {
+                    Line::Row(_) => Line::Column(column),
+                    Line::Column(_) => Line::Row(row),
+                });
+            }
+        }
+        changed
+    }
+
+    pub fn render(&self) -> String {
+        self.cells
+            .chunks(self.width.max(1))
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        '#' => '█',
+                        '.' => '·',
+                        _ => '?',
+                    })
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
+    }
+}
+
+fn propagate_line(cells: &[char], clue: &[usize]) -> Option<Vec<char>> {
+    let cell_options = ArrangementTable::new(cells, clue).cell_options()?;
+    Some(
+        cell_options
+            .can_be_damaged
+            .iter()
+            .zip(&cell_options.can_be_operational)
+            .map(|(damaged, operational)| match (damaged, operational) {
+                (true, false) => '#',
+                (false, true) => '.',
+                _ => '?',
+            })
+            .collect(),
+    )
+}
+
+fn parse_clue(line: &str, line_number: usize) -> Result<Vec<usize>, ParseCluesError> {
+    line.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|number| !number.is_empty())
+        .map(|number| {
+            number
+                .parse::<usize>()
+                .map_err(|_| ParseCluesError::InvalidClue {
+                    line_number,
+                    clue: line.to_string(),
+                })
+        })
+        .filter(|number| *number != Ok(0))
+        .collect()
+}
+
+impl Nonogram {
+    /// Reads a `rows:` section followed by a `columns:` section, one clue per line
+    /// (e.g. `3 1` or `3,1`, `0` for an empty line); `#` starts a comment.
+    pub fn parse(input: &str) -> Result<Self, ParseCluesError> {
+        let mut rows = None;
+        let mut columns = None;
+        let mut section: Option<&mut Vec<Vec<usize>>> = None;
+        for (index, line) in input.split("\n").enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            match line.trim_end_matches(':') {
+                "" => continue,
+                "rows" => section = Some(rows.insert(Vec::new())),
+                "columns" => section = Some(columns.insert(Vec::new())),
+                clue => section
+                    .as_mut()
+                    .ok_or(ParseCluesError::ClueOutsideSection {
+                        line_number: index + 1,
+                    })?
+                    .push(parse_clue(clue, index + 1)?),
+            }
+        }
+        Ok(Nonogram {
+            rows: rows.ok_or(ParseCluesError::MissingSection("rows"))?,
+            columns: columns.ok_or(ParseCluesError::MissingSection("columns"))?,
+        })
+    }
+
+    fn propagate(&self, grid: &mut Grid, mut pending: Vec<Line>) -> bool {
+        while let Some(line) = pending.pop() {
+            let clue = match line {
+                Line::Row(row) => &self.rows[row],
+                Line::Column(column) => &self.columns[column],
+            };
+            match propagate_line(&grid.line(&line), clue) {
+                Some(cells) => pending.extend(grid.set_line(&line, &cells)),
+                None => return false,
+            }
+        }
+        true
+    }
+
+    fn search(
+        &self,
+        mut grid: Grid,
+        pending: Vec<Line>,
+        on_solution: &mut dyn FnMut(&Grid) -> bool,
+    ) -> bool {
+        if !self.propagate(&mut grid, pending) {
+            return true;
+        }
+        let Some(unknown) = grid.cells.iter().position(|cell| *cell == '?') else {
+            return on_solution(&grid);
+        };
+        let (row, column) = (unknown / grid.width, unknown % grid.width);
+        for guess in ['#', '.'] {
+            let mut guessed = grid.clone();
+            guessed.cells[unknown] = guess;
+            if !self.search(
+                guessed,
+                vec![Line::Row(row), Line::Column(column)],
+                on_solution,
+            ) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn search_all(&self, on_solution: &mut dyn FnMut(&Grid) -> bool) {
+        let grid = Grid::new(self.columns.len(), self.rows.len());
+        let pending = (0..self.rows.len())
+            .map(Line::Row)
+            .chain((0..self.columns.len()).map(Line::Column))
+            .collect();
+        self.search(grid, pending, on_solution);
+    }
+
+    pub fn solve(&self) -> Option<Grid> {
+        let mut solution = None;
+        self.search_all(&mut |grid| {
+            solution = Some(grid.clone());
+            false
+        });
+        solution
+    }
+
+    pub fn count_solutions(&self) -> u64 {
+        let mut solutions = 0;
+        self.search_all(&mut |_| {
+            solutions += 1;
+            true
+        });
+        solutions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn clue_of(line: impl Iterator<Item = bool>) -> Vec<usize> {
+        let mut clue = Vec::new();
+        let mut run = 0;
+        for filled in line.chain([false]) {
+            if filled {
+                run += 1;
+            } else if run > 0 {
+                clue.push(run);
+                run = 0;
+            }
+        }
+        clue
+    }
+
+    fn clues_of(cells: &[Vec<bool>]) -> Nonogram {
+        let width = cells.first().map_or(0, |row| row.len());
+        Nonogram {
+            rows: cells
+                .iter()
+                .map(|row| clue_of(row.iter().copied()))
+                .collect(),
+            columns: (0..width)
+                .map(|column| clue_of(cells.iter().map(|row| row[column])))
+                .collect(),
+        }
+    }
+
+    fn brute_force_count(nonogram: &Nonogram) -> u64 {
+        let (height, width) = (nonogram.rows.len(), nonogram.columns.len());
+        (0u32..1 << (height * width))
+            .filter(|bits| {
+                let cells = (0..height)
+                    .map(|row| {
+                        (0..width)
+                            .map(|column| bits >> (row * width + column) & 1 == 1)
+                            .collect()
+                    })
+                    .collect::<Vec<Vec<bool>>>();
+                clues_of(&cells) == *nonogram
+            })
+            .count() as u64
+    }
+
+    #[test]
+    fn solves_a_unique_puzzle() {
+        let nonogram =
+            Nonogram::parse("rows:\n1\n1\n5\n1\n1\n# a plus sign\ncolumns:\n1\n1\n5\n1\n1\n")
+                .unwrap();
+        let grid = nonogram.solve().unwrap();
+        assert!(grid.is_solved());
+        assert_eq!(grid.render(), "··█··\n··█··\n█████\n··█··\n··█··\n");
+        assert_eq!(nonogram.count_solutions(), 1);
+    }
+
+    #[test]
+    fn counts_every_solution() {
+        let nonogram = Nonogram::parse("rows:\n1\n1\ncolumns:\n1\n1\n").unwrap();
+        assert_eq!(nonogram.count_solutions(), 2);
+        assert!(nonogram.solve().is_some());
+    }
+
+    #[test]
+    fn contradictory_clues_have_no_solution() {
+        let nonogram = Nonogram::parse("rows:\n2\ncolumns:\n0\n0\n").unwrap();
+        assert_eq!(nonogram.solve(), None);
+        assert_eq!(nonogram.count_solutions(), 0);
+    }
+
+    #[test]
+    fn reports_malformed_clue_files() {
+        assert_eq!(
+            Nonogram::parse("3\nrows:\n1\n"),
+            Err(ParseCluesError::ClueOutsideSection { line_number: 1 })
+        );
+        assert_eq!(
+            Nonogram::parse("rows:\n1 x\ncolumns:\n1\n"),
+            Err(ParseCluesError::InvalidClue {
+                line_number: 2,
+                clue: "1 x".to_string()
+            })
+        );
+        assert_eq!(
+            Nonogram::parse("rows:\n1\n"),
+            Err(ParseCluesError::MissingSection("columns"))
+        );
+        assert_eq!(
+            Nonogram::parse("columns:\n1\n"),
+            Err(ParseCluesError::MissingSection("rows"))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn matches_brute_force_on_small_grids(
+            cells in (1usize..4, 1usize..4).prop_flat_map(|(height, width)| {
+                prop::collection::vec(prop::collection::vec(any::<bool>(), width), height)
+            }),
+        ) {
+            let nonogram = clues_of(&cells);
+            prop_assert_eq!(nonogram.count_solutions(), brute_force_count(&nonogram));
+            let grid = nonogram.solve().unwrap();
+            let solved = (0..grid.height)
+                .map(|row| (0..grid.width).map(|column| grid.get(row, column) == '#').collect())
+                .collect::<Vec<Vec<bool>>>();
+            prop_assert_eq!(clues_of(&solved), nonogram);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberTheoryError {
+    Overflow,
+    InvalidModulus(i128),
+    NotInvertible { value: i128, modulus: i128 },
+    NoSolution,
+}
+
+impl std::fmt::Display for NumberTheoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberTheoryError::Overflow => write!(f, "arithmetic overflow"),
+            NumberTheoryError::InvalidModulus(modulus) => {
+                write!(f, "modulus must be positive, got {}", modulus)
+            }
+            NumberTheoryError::NotInvertible { value, modulus } => {
+                write!(f, "{} has no inverse modulo {}", value, modulus)
+            }
+            NumberTheoryError::NoSolution => write!(f, "congruences have no common solution"),
+        }
+    }
+}
+
+impl std::error::Error for NumberTheoryError {}
+
+fn to_signed(value: u128) -> Result<i128, NumberTheoryError> {
+    i128::try_from(value).map_err(|_| NumberTheoryError::Overflow)
+}
+
+fn check_modulus(modulus: i128) -> Result<(), NumberTheoryError> {
+    if modulus <= 0 {
+        return Err(NumberTheoryError::InvalidModulus(modulus));
+    }
+    Ok(())
+}
+
+pub fn gcd(a: i128, b: i128) -> Result<i128, NumberTheoryError> {
+    let mut a = a.unsigned_abs();
+    let mut b = b.unsigned_abs();
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    to_signed(a)
+}
+
+pub fn lcm(a: i128, b: i128) -> Result<i128, NumberTheoryError> {
+    if a == 0 || b == 0 {
+        return Ok(0);
+    }
+    let divisor = gcd(a, b)?;
+    (a / divisor)
+        .checked_mul(b)
+        .and_then(i128::checked_abs)
+        .ok_or(NumberTheoryError::Overflow)
+}
+
+pub fn lcm_of(numbers: &[i128]) -> Result<i128, NumberTheoryError> {
+    numbers
+        .iter()
+        .try_fold(1, |lowest_common, number| lcm(lowest_common, *number))
+}
+
+pub fn extended_gcd(a: i128, b: i128) -> Result<(i128, i128, i128), NumberTheoryError> {
+    let overflow = NumberTheoryError::Overflow;
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    let (mut old_t, mut t) = (0i128, 1i128);
+    while r != 0 {
+        let quotient = old_r.checked_div(r).ok_or(overflow)?;
+        (old_r, r) = (r, old_r - quotient * r);
+        let next_s = quotient
+            .checked_mul(s)
+            .and_then(|product| old_s.checked_sub(product))
+            .ok_or(overflow)?;
+        (old_s, s) = (s, next_s);
+        let next_t = quotient
+            .checked_mul(t)
+            .and_then(|product| old_t.checked_sub(product))
+            .ok_or(overflow)?;
+        (old_t, t) = (t, next_t);
+    }
+    if old_r < 0 {
+        return Ok((
+            old_r.checked_neg().ok_or(overflow)?,
+            old_s.checked_neg().ok_or(overflow)?,
+            old_t.checked_neg().ok_or(overflow)?,
+        ));
+    }
+    Ok((old_r, old_s, old_t))
+}
+
+pub fn mul_mod(a: i128, b: i128, modulus: i128) -> Result<i128, NumberTheoryError> {
+    check_modulus(modulus)?;
+    let modulus = modulus as u128;
+    let mut a = a.rem_euclid(modulus as i128) as u128;
+    let mut b = b.rem_euclid(modulus as i128) as u128;
+    let mut product = 0u128;
+    while b > 0 {
+        if b & 1 == 1 {
+            product = (product + a) % modulus;
+        }
+        a = (a + a) % modulus;
+        b >>= 1;
+    }
+    Ok(product as i128)
+}
+
+pub fn mod_inverse(value: i128, modulus: i128) -> Result<i128, NumberTheoryError> {
+    check_modulus(modulus)?;
+    let (divisor, x, _) = extended_gcd(value.rem_euclid(modulus), modulus)?;
+    if divisor != 1 {
+        return Err(NumberTheoryError::NotInvertible { value, modulus });
+    }
+    Ok(x.rem_euclid(modulus))
+}
+
+pub fn crt_pair(
+    (first_remainder, first_modulus): (i128, i128),
+    (second_remainder, second_modulus): (i128, i128),
+) -> Result<(i128, i128), NumberTheoryError> {
+    check_modulus(first_modulus)?;
+    check_modulus(second_modulus)?;
+    let first_remainder = first_remainder.rem_euclid(first_modulus);
+    let second_remainder = second_remainder.rem_euclid(second_modulus);
+    let divisor = gcd(first_modulus, second_modulus)?;
+    let difference = second_remainder - first_remainder;
+    if difference % divisor != 0 {
+        return Err(NumberTheoryError::NoSolution);
+    }
+    let reduced_modulus = second_modulus / divisor;
+    let step = mul_mod(
+        difference / divisor,
+        mod_inverse(first_modulus / divisor, reduced_modulus)?,
+        reduced_modulus,
+    )?;
+    let modulus = first_modulus
+        .checked_mul(reduced_modulus)
+        .ok_or(NumberTheoryError::Overflow)?;
+    let remainder = first_modulus
+        .checked_mul(step)
+        .and_then(|offset| offset.checked_add(first_remainder))
+        .ok_or(NumberTheoryError::Overflow)?;
+    Ok((remainder, modulus))
+}
+
+pub fn crt(congruences: &[(i128, i128)]) -> Result<(i128, i128), NumberTheoryError> {
+    congruences.iter().try_fold((0, 1), |combined, congruence| {
+        crt_pair(combined, *congruence)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn gcd_and_lcm_handle_zero_and_signs() {
+        assert_eq!(gcd(0, 0), Ok(0));
+        assert_eq!(gcd(-12, 18), Ok(6));
+        assert_eq!(gcd(0, -7), Ok(7));
+        assert_eq!(lcm(0, 5), Ok(0));
+        assert_eq!(lcm(5, 0), Ok(0));
+        assert_eq!(lcm(-4, 6), Ok(12));
+        assert_eq!(lcm_of(&[]), Ok(1));
+    }
+
+    #[test]
+    fn overflowing_results_are_errors() {
+        assert_eq!(gcd(i128::MIN, 0), Err(NumberTheoryError::Overflow));
+        assert_eq!(
+            lcm(i128::MAX, i128::MAX - 1),
+            Err(NumberTheoryError::Overflow)
+        );
+        assert_eq!(
+            extended_gcd(i128::MIN, -1),
+            Err(NumberTheoryError::Overflow)
+        );
+        assert_eq!(
+            crt_pair((0, i128::MAX), (1, i128::MAX - 1)),
+            Err(NumberTheoryError::Overflow)
+        );
+    }
+
+    #[test]
+    fn extended_gcd_returns_bezout_coefficients() {
+        for (a, b) in [(-12, 18), (240, 46), (0, -5), (7, 0)] {
+            let (divisor, s, t) = extended_gcd(a, b).unwrap();
+            assert_eq!(divisor, gcd(a, b).unwrap());
+            assert_eq!(a * s + b * t, divisor);
+        }
+    }
+
+    #[test]
+    fn mul_mod_near_the_top_of_the_range() {
+        assert_eq!(mul_mod(i128::MAX - 1, i128::MAX - 2, i128::MAX), Ok(2));
+        assert_eq!(mul_mod(i128::MIN, i128::MIN, i128::MAX), Ok(1));
+        assert_eq!(mul_mod(3, 4, 0), Err(NumberTheoryError::InvalidModulus(0)));
+    }
+
+    #[test]
+    fn mod_inverse_rejects_shared_factors() {
+        assert_eq!(mod_inverse(-3, 7), Ok(2));
+        assert_eq!(
+            mod_inverse(4, 6),
+            Err(NumberTheoryError::NotInvertible {
+                value: 4,
+                modulus: 6
+            })
+        );
+        assert_eq!(
+            mod_inverse(3, -7),
+            Err(NumberTheoryError::InvalidModulus(-7))
+        );
+    }
+
+    #[test]
+    fn crt_pair_with_shared_factors() {
+        assert_eq!(crt_pair((2, 4), (4, 6)), Ok((10, 12)));
+        assert_eq!(crt_pair((1, 4), (2, 6)), Err(NumberTheoryError::NoSolution));
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Ok((23, 105)));
+    }
+
+    proptest! {
+        #[test]
+        fn crt_pair_matches_brute_force(
+            first_remainder in -50i128..50,
+            first_modulus in 1i128..30,
+            second_remainder in -50i128..50,
+            second_modulus in 1i128..30,
+        ) {
+            let expected = (0..lcm(first_modulus, second_modulus).unwrap()).find(|x| {
+                (x - first_remainder).rem_euclid(first_modulus) == 0
+                    && (x - second_remainder).rem_euclid(second_modulus) == 0
+            });
+            let combined = crt_pair(
+                (first_remainder, first_modulus),
+                (second_remainder, second_modulus),
+            );
+            match expected {
+                Some(x) => prop_assert_eq!(combined, Ok((x, lcm(first_modulus, second_modulus).unwrap()))),
+                None => prop_assert_eq!(combined, Err(NumberTheoryError::NoSolution)),
+            }
+        }
+    }
+}